@@ -0,0 +1,342 @@
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use serde::Deserialize;
+use serde::de::{self, Deserializer};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::input::InputFormat;
+use crate::resumes::{Available, Format, PageRanges};
+
+/// A `resume.toml` project file: named build profiles, each a saved `export` invocation.
+///
+/// ```toml
+/// [profiles.backend]
+/// input = "resume.json"
+/// overlays = ["acme.yaml"]
+/// include-tags = ["backend"]
+/// template = "basic"
+/// output = "out/backend.pdf"
+///
+/// [profiles.backend.params]
+/// font = "Source Serif 4"
+///
+/// [profiles.handout]
+/// templates = ["basic"]
+/// formats = ["pdf", "docx", "html"]
+/// ```
+///
+/// A profile with `templates` or `formats` builds one output for each combination.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Profile {
+    #[serde(default = "default_input")]
+    pub input: PathBuf,
+    #[serde(default, deserialize_with = "optional_value_enum")]
+    pub input_format: Option<InputFormat>,
+    #[serde(default)]
+    pub overlays: Vec<PathBuf>,
+    #[serde(default)]
+    pub include_tags: Vec<String>,
+    #[serde(default)]
+    pub exclude_tags: Vec<String>,
+    #[serde(default, deserialize_with = "value_enum")]
+    pub template: Available,
+    /// Builds with each of these templates instead of `template`.
+    #[serde(default, deserialize_with = "value_enums")]
+    pub templates: Vec<Available>,
+    /// Passed to the template as `sys.inputs.params`.
+    #[serde(default)]
+    pub params: serde_json::Map<String, serde_json::Value>,
+    /// Defaults to the profile name with the format's extension.
+    #[serde(default)]
+    pub output: PathBuf,
+    #[serde(default, deserialize_with = "value_enum")]
+    pub format: Format,
+    /// Builds each of these formats instead of `format`.
+    #[serde(default, deserialize_with = "value_enums")]
+    pub formats: Vec<Format>,
+    /// Line width for text exports; 0 turns wrapping off.
+    #[serde(default = "default_wrap")]
+    pub wrap: usize,
+    /// Resolution of PNG exports, in pixels per inch.
+    #[serde(default = "default_ppi", deserialize_with = "resolution")]
+    pub ppi: f32,
+    /// Pages to write for image exports, such as `"1"` or `"2-"`.
+    #[serde(default, deserialize_with = "parsed")]
+    pub pages: PageRanges,
+    #[serde(default)]
+    pub strict: bool,
+    #[serde(default)]
+    pub extra_namespace: bool,
+}
+
+/// What a profile reads: profiles that agree on it can share one parsed resume.
+pub type Source = (PathBuf, Option<InputFormat>, Vec<PathBuf>, bool);
+
+impl Profile {
+    /// One profile per template and format this one builds. When there are several, the
+    /// output takes each format's extension and each template's name.
+    pub fn outputs(&self) -> Vec<Profile> {
+        let templates = match self.templates.as_slice() {
+            [] => vec![self.template.clone()],
+            templates => templates.to_vec(),
+        };
+        let formats = match self.formats.as_slice() {
+            [] => vec![self.format],
+            formats => formats.to_vec(),
+        };
+        let mut outputs = Vec::new();
+        for template in &templates {
+            for &format in &formats {
+                let mut output = self.output.clone();
+                if templates.len() > 1 {
+                    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+                    output.set_file_name(format!("{}-{}", stem, template));
+                }
+                if formats.len() > 1 || templates.len() > 1 {
+                    output.set_extension(format.extension());
+                }
+                outputs.push(Profile {
+                    template: template.clone(),
+                    templates: Vec::new(),
+                    format,
+                    formats: Vec::new(),
+                    output,
+                    ..self.clone()
+                });
+            }
+        }
+        outputs
+    }
+
+    pub fn source(&self) -> Source {
+        (
+            self.input.clone(),
+            self.input_format,
+            self.overlays.clone(),
+            self.strict,
+        )
+    }
+}
+
+fn default_input() -> PathBuf {
+    PathBuf::from("resume.json")
+}
+
+pub fn default_wrap() -> usize {
+    80
+}
+
+pub fn default_ppi() -> f32 {
+    144.0
+}
+
+/// Reads a `--ppi` flag, which has to be a positive number.
+pub fn parse_ppi(arg: &str) -> Result<f32, String> {
+    let ppi = arg.parse().map_err(|e| format!("{}", e))?;
+    positive_ppi(ppi)
+}
+
+fn positive_ppi(ppi: f32) -> Result<f32, String> {
+    if ppi.is_finite() && ppi > 0.0 {
+        Ok(ppi)
+    } else {
+        Err(format!(
+            "the resolution must be a positive number, not {}",
+            ppi
+        ))
+    }
+}
+
+// Accepts the same spellings as the command line flags.
+fn value_enum<'de, D: Deserializer<'de>, T: ValueEnum>(deserializer: D) -> Result<T, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    T::from_str(&name, false).map_err(de::Error::custom)
+}
+
+fn value_enums<'de, D: Deserializer<'de>, T: ValueEnum>(
+    deserializer: D,
+) -> Result<Vec<T>, D::Error> {
+    let names = Vec::<String>::deserialize(deserializer)?;
+    names
+        .iter()
+        .map(|name| T::from_str(name, false).map_err(de::Error::custom))
+        .collect()
+}
+
+fn parsed<'de, D: Deserializer<'de>, T>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(de::Error::custom)
+}
+
+fn resolution<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    positive_ppi(f32::deserialize(deserializer)?).map_err(de::Error::custom)
+}
+
+fn optional_value_enum<'de, D: Deserializer<'de>, T: ValueEnum>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error> {
+    value_enum(deserializer).map(Some)
+}
+
+impl Config {
+    /// Reads a project file. Paths in profiles are relative to the file itself.
+    pub fn load(path: &Path) -> Result<Self> {
+        let data =
+            fs::read_to_string(path).with_context(|| format!("Could not read {:?}", path))?;
+        let mut config: Self =
+            toml::from_str(&data).with_context(|| format!("Could not parse {:?}", path))?;
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        for (name, profile) in &mut config.profiles {
+            if profile.output.as_os_str().is_empty() {
+                profile.output = PathBuf::from(format!("{}.{}", name, profile.format.extension()));
+            }
+            profile.input = base.join(&profile.input);
+            profile.output = base.join(&profile.output);
+            for overlay in &mut profile.overlays {
+                *overlay = base.join(&*overlay);
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn profile(&self, name: &str) -> Result<&Profile> {
+        match self.profiles.get(name) {
+            Some(profile) => Ok(profile),
+            None if self.profiles.is_empty() => bail!("No profiles are defined"),
+            None => bail!(
+                "Unknown profile `{}`; expected one of: {}",
+                name,
+                self.profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_resolves_paths_and_defaults() {
+        let dir = std::env::temp_dir().join(format!("resume-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("resume.toml");
+        fs::write(
+            &path,
+            r#"
+            [profiles.backend]
+            overlays = ["acme.yaml"]
+            include-tags = ["backend"]
+            template = "basic"
+            params = { font = "Source Serif 4" }
+
+            [profiles.research]
+            input = "cv.dhall"
+            input-format = "dhall"
+            template = "basic"
+            output = "out/research.txt"
+            format = "text"
+            wrap = 72
+
+            [profiles.thumbnail]
+            template = "basic"
+            format = "png"
+            ppi = 48
+            pages = "1"
+            "#,
+        )
+        .unwrap();
+
+        let config = Config::load(&path).unwrap();
+        let backend = config.profile("backend").unwrap();
+        assert_eq!(backend.input, dir.join("resume.json"));
+        assert_eq!(backend.overlays, [dir.join("acme.yaml")]);
+        assert_eq!(backend.output, dir.join("backend.pdf"));
+        assert_eq!(backend.params["font"], "Source Serif 4");
+        assert_eq!(backend.wrap, 80);
+
+        let research = config.profile("research").unwrap();
+        assert_eq!(research.input_format, Some(InputFormat::Dhall));
+        assert_eq!(research.output, dir.join("out/research.txt"));
+        assert_eq!((research.format, research.wrap), (Format::Text, 72));
+
+        let thumbnail = config.profile("thumbnail").unwrap();
+        assert_eq!(thumbnail.output, dir.join("thumbnail.png"));
+        assert_eq!(thumbnail.ppi, 48.0);
+        assert_eq!(thumbnail.pages.select(3), Ok(vec![1]));
+
+        let error = config.profile("sales").unwrap_err().to_string();
+        assert!(error.contains("backend, research, thumbnail"), "{}", error);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_outputs_cover_every_template_and_format() {
+        let config: Config = toml::from_str(
+            "[profiles.one]\ntemplate = \"basic\"\noutput = \"cv.pdf\"\n\n[profiles.many]\ntemplates = [\"basic\"]\nformats = [\"pdf\", \"docx\"]\noutput = \"out/cv.pdf\"\n",
+        )
+        .unwrap();
+        let outputs = |name: &str| -> Vec<(String, Format, PathBuf)> {
+            config.profiles[name]
+                .outputs()
+                .into_iter()
+                .map(|p| (p.template.to_string(), p.format, p.output))
+                .collect()
+        };
+        assert_eq!(
+            outputs("one"),
+            [("basic".to_string(), Format::Pdf, PathBuf::from("cv.pdf"))]
+        );
+        assert_eq!(
+            outputs("many"),
+            [
+                (
+                    "basic".to_string(),
+                    Format::Pdf,
+                    PathBuf::from("out/cv.pdf")
+                ),
+                (
+                    "basic".to_string(),
+                    Format::Docx,
+                    PathBuf::from("out/cv.docx")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rejects_unknown_keys_and_templates() {
+        let error = toml::from_str::<Config>("[profiles.a]\ntemplate = \"fancy\"\n").unwrap_err();
+        assert!(error.to_string().contains("fancy"), "{}", error);
+        assert!(
+            toml::from_str::<Config>("[profiles.a]\ntemplate = \"basic\"\ntag = []\n").is_err()
+        );
+    }
+
+    #[test]
+    fn test_rejects_bad_resolutions() {
+        assert_eq!(parse_ppi("72"), Ok(72.0));
+        for arg in ["0", "-1", "inf", "NaN", "wide"] {
+            assert!(parse_ppi(arg).is_err(), "{}", arg);
+        }
+        let error = toml::from_str::<Config>("[profiles.a]\nppi = 0\n").unwrap_err();
+        assert!(error.to_string().contains("positive"), "{}", error);
+    }
+}
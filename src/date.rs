@@ -0,0 +1,435 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "schema")]
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+#[cfg(feature = "schema")]
+use std::borrow::Cow;
+
+#[cfg(feature = "typst")]
+use typst::foundations::{Dict, IntoValue, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Precision {
+    Year,
+    Month,
+    Day,
+}
+
+impl fmt::Display for Precision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Year => write!(f, "year"),
+            Self::Month => write!(f, "month"),
+            Self::Day => write!(f, "day"),
+        }
+    }
+}
+
+/// An ISO 8601 calendar date that may stop at the year or month, as JSON Resume allows.
+///
+/// Dates order chronologically; a less precise date sorts before the more precise
+/// dates it contains, so `2023` < `2023-01` < `2023-01-01`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PartialDate {
+    pub year: u16,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+}
+
+/// The end of a date range, which may still be ongoing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EndDate {
+    Date(PartialDate),
+    Present,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateError {
+    Format(String),
+    Month(String, u8),
+    Day(String, u8),
+}
+
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Format(s) => write!(
+                f,
+                "`{}` is not an ISO 8601 date (expected YYYY, YYYY-MM or YYYY-MM-DD)",
+                s
+            ),
+            Self::Month(s, month) => write!(f, "`{}` has no month {}", s, month),
+            Self::Day(s, day) => write!(f, "`{}` has no day {} in that month", s, day),
+        }
+    }
+}
+
+impl std::error::Error for DateError {}
+
+pub fn is_leap_year(year: u16) -> bool {
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+}
+
+pub fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl PartialDate {
+    pub fn precision(&self) -> Precision {
+        match (self.month, self.day) {
+            (Some(_), Some(_)) => Precision::Day,
+            (Some(_), None) => Precision::Month,
+            _ => Precision::Year,
+        }
+    }
+
+    /// The current UTC date.
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self::from_days_since_epoch((secs / 86_400) as i64)
+    }
+
+    // Howard Hinnant's `civil_from_days`, restricted to the proleptic Gregorian calendar.
+    fn from_days_since_epoch(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        Self {
+            year: year as u16,
+            month: Some(month as u8),
+            day: Some(day as u8),
+        }
+    }
+
+    /// The earliest day this date could refer to, e.g. `2023-04` becomes `2023-04-01`.
+    pub fn first_day(&self) -> Self {
+        Self {
+            year: self.year,
+            month: Some(self.month.unwrap_or(1)),
+            day: Some(self.day.unwrap_or(1)),
+        }
+    }
+
+    /// The latest day this date could refer to, e.g. `2023-04` becomes `2023-04-30`.
+    pub fn last_day(&self) -> Self {
+        let month = self.month.unwrap_or(12);
+        Self {
+            year: self.year,
+            month: Some(month),
+            day: Some(self.day.unwrap_or_else(|| days_in_month(self.year, month))),
+        }
+    }
+
+    /// Months since year zero, handy for month-granularity arithmetic.
+    pub fn month_index(&self) -> i32 {
+        i32::from(self.year) * 12 + i32::from(self.month.unwrap_or(1)) - 1
+    }
+}
+
+fn digits<T: FromStr>(part: &str, len: usize) -> Option<T> {
+    (part.len() == len && part.bytes().all(|b| b.is_ascii_digit()))
+        .then(|| part.parse().ok())
+        .flatten()
+}
+
+impl FromStr for PartialDate {
+    type Err = DateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let format = || DateError::Format(s.to_string());
+        let mut parts = s.split('-');
+
+        let year = parts.next().and_then(|p| digits(p, 4)).ok_or_else(format)?;
+        let month = match parts.next() {
+            Some(p) => Some(digits::<u8>(p, 2).ok_or_else(format)?),
+            None => None,
+        };
+        let day = match parts.next() {
+            Some(p) => Some(digits::<u8>(p, 2).ok_or_else(format)?),
+            None => None,
+        };
+        if parts.next().is_some() {
+            return Err(format());
+        }
+
+        if let Some(month) = month
+            && !(1..=12).contains(&month)
+        {
+            return Err(DateError::Month(s.to_string(), month));
+        }
+        if let (Some(month), Some(day)) = (month, day)
+            && !(1..=days_in_month(year, month)).contains(&day)
+        {
+            return Err(DateError::Day(s.to_string(), day));
+        }
+
+        Ok(Self { year, month, day })
+    }
+}
+
+impl fmt::Display for PartialDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        if let Some(month) = self.month {
+            write!(f, "-{:02}", month)?;
+        }
+        if let Some(day) = self.day {
+            write!(f, "-{:02}", day)?;
+        }
+        Ok(())
+    }
+}
+
+impl EndDate {
+    pub fn date(&self) -> Option<PartialDate> {
+        match self {
+            Self::Date(date) => Some(*date),
+            Self::Present => None,
+        }
+    }
+}
+
+impl FromStr for EndDate {
+    type Err = DateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("present") || s.eq_ignore_ascii_case("ongoing") {
+            Ok(Self::Present)
+        } else {
+            s.parse().map(Self::Date)
+        }
+    }
+}
+
+impl fmt::Display for EndDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Date(date) => date.fmt(f),
+            Self::Present => write!(f, "present"),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for PartialDate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for PartialDate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for EndDate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for EndDate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+// The pattern only checks the shape; impossible days like `2023-02-30` are caught by `validate`.
+#[cfg(feature = "schema")]
+impl JsonSchema for PartialDate {
+    fn schema_name() -> Cow<'static, str> {
+        "PartialDate".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "description": "An ISO 8601 date with year, month or day precision",
+            "pattern": "^[0-9]{4}(-(0[1-9]|1[0-2])(-(0[1-9]|[12][0-9]|3[01]))?)?$",
+            "examples": ["2023", "2023-04", "2023-04-01"],
+        })
+    }
+}
+
+#[cfg(feature = "schema")]
+impl JsonSchema for EndDate {
+    fn schema_name() -> Cow<'static, str> {
+        "EndDate".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "anyOf": [
+                generator.subschema_for::<PartialDate>(),
+                {
+                    "type": "string",
+                    "description": "The entry is still ongoing",
+                    "pattern": "^([Pp][Rr][Ee][Ss][Ee][Nn][Tt]|[Oo][Nn][Gg][Oo][Ii][Nn][Gg])$",
+                    "examples": ["present"],
+                },
+            ]
+        })
+    }
+}
+
+#[cfg(feature = "typst")]
+impl IntoValue for PartialDate {
+    fn into_value(self) -> Value {
+        let mut dict = Dict::new();
+        dict.insert("year".into(), (self.year as i64).into_value());
+        if let Some(month) = self.month {
+            dict.insert("month".into(), (month as i64).into_value());
+        }
+        if let Some(day) = self.day {
+            dict.insert("day".into(), (day as i64).into_value());
+        }
+        dict.insert(
+            "precision".into(),
+            self.precision().to_string().into_value(),
+        );
+        Value::Dict(dict)
+    }
+}
+
+#[cfg(feature = "typst")]
+impl IntoValue for EndDate {
+    fn into_value(self) -> Value {
+        match self {
+            Self::Date(date) => date.into_value(),
+            Self::Present => {
+                let mut dict = Dict::new();
+                dict.insert("precision".into(), "present".into_value());
+                Value::Dict(dict)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_every_precision() {
+        let year: PartialDate = "2023".parse().unwrap();
+        let month: PartialDate = "2023-07".parse().unwrap();
+        let day: PartialDate = "2024-02-29".parse().unwrap();
+        assert_eq!(year.precision(), Precision::Year);
+        assert_eq!(month.precision(), Precision::Month);
+        assert_eq!(day.precision(), Precision::Day);
+        assert_eq!(day.to_string(), "2024-02-29");
+    }
+
+    #[test]
+    fn test_days_since_epoch() {
+        let date = |days| PartialDate::from_days_since_epoch(days).to_string();
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(date(11_016), "2000-02-29");
+        assert_eq!(date(19_723), "2024-01-01");
+    }
+
+    #[test]
+    fn test_widens_to_days() {
+        let date: PartialDate = "2024-02".parse().unwrap();
+        assert_eq!(date.first_day().to_string(), "2024-02-01");
+        assert_eq!(date.last_day().to_string(), "2024-02-29");
+        let year: PartialDate = "2023".parse().unwrap();
+        assert_eq!(year.last_day().to_string(), "2023-12-31");
+    }
+
+    #[test]
+    fn test_orders_chronologically() {
+        let mut dates: Vec<PartialDate> = ["2021-03", "2020", "2021-03-02", "2020-12"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        dates.sort();
+        let sorted: Vec<String> = dates.iter().map(|d| d.to_string()).collect();
+        assert_eq!(sorted, ["2020", "2020-12", "2021-03", "2021-03-02"]);
+
+        let ongoing: EndDate = "Present".parse().unwrap();
+        assert!(ongoing > EndDate::Date("2999-12-31".parse().unwrap()));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serializes_to_json_resume_strings() {
+        let end: EndDate = serde_json::from_str(r#""ongoing""#).unwrap();
+        assert_eq!(end, EndDate::Present);
+        assert_eq!(serde_json::to_string(&end).unwrap(), r#""present""#);
+
+        let date: PartialDate = serde_json::from_str(r#""2019-04""#).unwrap();
+        assert_eq!(serde_json::to_string(&date).unwrap(), r#""2019-04""#);
+    }
+
+    #[test]
+    #[cfg(feature = "typst")]
+    fn test_into_typst_dict() {
+        let date: PartialDate = "2019-04".parse().unwrap();
+        let Value::Dict(dict) = date.into_value() else {
+            panic!("PartialDate should convert to a Dict");
+        };
+        assert_eq!(dict.get("year").unwrap(), &2019i64.into_value());
+        assert_eq!(dict.get("precision").unwrap(), &"month".into_value());
+        assert!(dict.get("day").is_err());
+    }
+
+    #[test]
+    fn test_rejects_impossible_dates() {
+        assert!(matches!(
+            "2023-19-39".parse::<PartialDate>(),
+            Err(DateError::Month(_, 19))
+        ));
+        assert!(matches!(
+            "2023-02-29".parse::<PartialDate>(),
+            Err(DateError::Day(_, 29))
+        ));
+        assert!(matches!(
+            "2023-04-31".parse::<PartialDate>(),
+            Err(DateError::Day(_, 31))
+        ));
+        assert!("2023-00".parse::<PartialDate>().is_err());
+        assert!("1900-02-29".parse::<PartialDate>().is_err());
+        assert!("2000-02-29".parse::<PartialDate>().is_ok());
+    }
+
+    #[test]
+    fn test_rejects_malformed_strings() {
+        for s in [
+            "",
+            "23",
+            "2023-1",
+            "2023-01-01T00:00",
+            "2023/01",
+            "２０２３",
+            "2023-",
+        ] {
+            assert!(s.parse::<PartialDate>().is_err(), "{} should not parse", s);
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use anyhow::Result;
+use std::fmt::Write;
+
+use crate::date::PartialDate;
+use crate::outline::{Entry, Outline};
+use crate::package::{self, escape};
+
+const W: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+const R: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/>
+<Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+<Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/>
+<Override PartName="/word/numbering.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.numbering+xml"/>
+<Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>
+<Override PartName="/docProps/app.xml" ContentType="application/vnd.openxmlformats-officedocument.extended-properties+xml"/>
+</Types>
+"#;
+
+const PACKAGE_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
+<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/>
+<Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/extended-properties" Target="docProps/app.xml"/>
+</Relationships>
+"#;
+
+const APP: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Properties xmlns="http://schemas.openxmlformats.org/officeDocument/2006/extended-properties">
+<Application>resume-bakery</Application>
+</Properties>
+"#;
+
+// One bullet style, used for every list.
+const NUMBERING: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:numbering xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+<w:abstractNum w:abstractNumId="0">
+<w:multiLevelType w:val="singleLevel"/>
+<w:lvl w:ilvl="0"><w:start w:val="1"/><w:numFmt w:val="bullet"/><w:lvlText w:val="•"/><w:lvlJc w:val="left"/><w:pPr><w:ind w:left="720" w:hanging="360"/></w:pPr></w:lvl>
+</w:abstractNum>
+<w:num w:numId="1"><w:abstractNumId w:val="0"/></w:num>
+</w:numbering>
+"#;
+
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+<w:docDefaults>
+<w:rPrDefault><w:rPr><w:rFonts w:ascii="Calibri" w:hAnsi="Calibri" w:eastAsia="Calibri" w:cs="Calibri"/><w:sz w:val="22"/><w:szCs w:val="22"/><w:lang w:val="{lang}"/></w:rPr></w:rPrDefault>
+<w:pPrDefault><w:pPr><w:spacing w:after="80" w:line="264" w:lineRule="auto"/></w:pPr></w:pPrDefault>
+</w:docDefaults>
+<w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/><w:qFormat/></w:style>
+<w:style w:type="paragraph" w:styleId="Title"><w:name w:val="Title"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:spacing w:after="40"/></w:pPr><w:rPr><w:rFonts w:ascii="Cambria" w:hAnsi="Cambria"/><w:b/><w:sz w:val="48"/><w:szCs w:val="48"/></w:rPr></w:style>
+<w:style w:type="paragraph" w:styleId="Subtitle"><w:name w:val="Subtitle"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:rPr><w:color w:val="4C566A"/><w:sz w:val="28"/><w:szCs w:val="28"/></w:rPr></w:style>
+<w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:pBdr><w:bottom w:val="single" w:sz="4" w:space="1" w:color="D8DEE9"/></w:pBdr><w:spacing w:before="320" w:after="120"/><w:outlineLvl w:val="0"/></w:pPr><w:rPr><w:rFonts w:ascii="Cambria" w:hAnsi="Cambria"/><w:b/><w:color w:val="5E81AC"/><w:sz w:val="30"/><w:szCs w:val="30"/></w:rPr></w:style>
+<w:style w:type="paragraph" w:styleId="Heading2"><w:name w:val="heading 2"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:spacing w:before="200" w:after="40"/><w:outlineLvl w:val="1"/></w:pPr><w:rPr><w:b/><w:sz w:val="24"/><w:szCs w:val="24"/></w:rPr></w:style>
+<w:style w:type="paragraph" w:styleId="ListBullet"><w:name w:val="List Bullet"/><w:basedOn w:val="Normal"/><w:qFormat/><w:pPr><w:numPr><w:numId w:val="1"/></w:numPr><w:spacing w:after="40"/></w:pPr></w:style>
+<w:style w:type="character" w:styleId="Hyperlink"><w:name w:val="Hyperlink"/><w:rPr><w:color w:val="5E81AC"/><w:u w:val="single"/></w:rPr></w:style>
+</w:styles>
+"#;
+
+/// Renders an Office Open XML document with real headings, bullet lists and hyperlinks.
+pub fn render(outline: &Outline, lang: &str, today: PartialDate) -> Result<Vec<u8>> {
+    let mut body = Body::default();
+    if let Some(name) = &outline.name {
+        body.paragraph(Some("Title"), &run(name, false));
+    }
+    if let Some(label) = &outline.label {
+        body.paragraph(Some("Subtitle"), &run(label, false));
+    }
+    if !outline.contacts.is_empty() {
+        let mut runs = String::new();
+        for (i, contact) in outline.contacts.iter().enumerate() {
+            if i > 0 {
+                runs.push_str(&run(" · ", false));
+            }
+            runs.push_str(&body.link(&contact.text, contact.href.as_deref()));
+        }
+        body.paragraph(None, &runs);
+    }
+    if let Some(summary) = &outline.summary {
+        body.paragraph(None, &run(summary, false));
+    }
+    for section in &outline.sections {
+        body.paragraph(Some("Heading1"), &run(section.title, false));
+        for entry in &section.entries {
+            body.entry(entry);
+        }
+    }
+
+    let document = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <w:document xmlns:w=\"{W}\" xmlns:r=\"{R}\"><w:body>{}\
+         <w:sectPr><w:pgSz w:w=\"12240\" w:h=\"15840\"/>\
+         <w:pgMar w:top=\"1080\" w:right=\"1080\" w:bottom=\"1080\" w:left=\"1080\" \
+         w:header=\"720\" w:footer=\"720\" w:gutter=\"0\"/></w:sectPr>\
+         </w:body></w:document>\n",
+        body.xml
+    );
+
+    let mut rels = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\n\
+         <Relationship Id=\"rId1\" Type=\"{R}/styles\" Target=\"styles.xml\"/>\n\
+         <Relationship Id=\"rId2\" Type=\"{R}/numbering\" Target=\"numbering.xml\"/>\n"
+    );
+    for (i, href) in body.links.iter().enumerate() {
+        let _ = writeln!(
+            rels,
+            "<Relationship Id=\"rIdLink{}\" Type=\"{R}/hyperlink\" Target=\"{}\" \
+             TargetMode=\"External\"/>",
+            i + 1,
+            escape(href)
+        );
+    }
+    rels.push_str("</Relationships>\n");
+
+    package::zip([
+        ("[Content_Types].xml", CONTENT_TYPES.to_string()),
+        ("_rels/.rels", PACKAGE_RELS.to_string()),
+        ("docProps/core.xml", core(outline, lang, today)),
+        ("docProps/app.xml", APP.to_string()),
+        ("word/document.xml", document),
+        ("word/_rels/document.xml.rels", rels),
+        ("word/styles.xml", STYLES.replace("{lang}", &escape(lang))),
+        ("word/numbering.xml", NUMBERING.to_string()),
+    ])
+}
+
+// The document properties Word shows under File > Info.
+fn core(outline: &Outline, lang: &str, today: PartialDate) -> String {
+    let mut core = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <cp:coreProperties \
+         xmlns:cp=\"http://schemas.openxmlformats.org/package/2006/metadata/core-properties\" \
+         xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+         xmlns:dcterms=\"http://purl.org/dc/terms/\" \
+         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">\n",
+    );
+    let _ = writeln!(core, "<dc:title>{}</dc:title>", escape(&outline.title()));
+    if let Some(name) = &outline.name {
+        let _ = writeln!(core, "<dc:creator>{}</dc:creator>", escape(name));
+    }
+    if let Some(label) = &outline.label {
+        let _ = writeln!(core, "<dc:subject>{}</dc:subject>", escape(label));
+    }
+    let keywords = outline.keywords();
+    if !keywords.is_empty() {
+        let _ = writeln!(
+            core,
+            "<cp:keywords>{}</cp:keywords>",
+            escape(&keywords.join(", "))
+        );
+    }
+    let _ = writeln!(core, "<dc:language>{}</dc:language>", escape(lang));
+    let today = today.first_day();
+    for element in ["created", "modified"] {
+        let _ = writeln!(
+            core,
+            "<dcterms:{element} xsi:type=\"dcterms:W3CDTF\">{}T00:00:00Z</dcterms:{element}>",
+            today
+        );
+    }
+    core.push_str("</cp:coreProperties>\n");
+    core
+}
+
+/// The paragraphs of the document, and the hyperlink targets they refer to.
+#[derive(Default)]
+struct Body {
+    xml: String,
+    links: Vec<String>,
+}
+
+impl Body {
+    fn paragraph(&mut self, style: Option<&str>, runs: &str) {
+        self.xml.push_str("<w:p>");
+        if let Some(style) = style {
+            let _ = write!(self.xml, "<w:pPr><w:pStyle w:val=\"{}\"/></w:pPr>", style);
+        }
+        self.xml.push_str(runs);
+        self.xml.push_str("</w:p>");
+    }
+
+    // Hyperlinks point at a relationship, which the package lists separately.
+    fn link(&mut self, text: &str, href: Option<&str>) -> String {
+        let Some(href) = href else {
+            return run(text, false);
+        };
+        self.links.push(href.to_string());
+        format!(
+            "<w:hyperlink r:id=\"rIdLink{}\" w:history=\"1\"><w:r><w:rPr><w:rStyle \
+             w:val=\"Hyperlink\"/></w:rPr><w:t xml:space=\"preserve\">{}</w:t></w:r></w:hyperlink>",
+            self.links.len(),
+            escape(text)
+        )
+    }
+
+    fn entry(&mut self, entry: &Entry) {
+        let title = self.link(&entry.title, entry.url.as_deref());
+        self.paragraph(Some("Heading2"), &title);
+
+        let mut details = String::new();
+        if let Some(subtitle) = &entry.subtitle {
+            details.push_str(&run(subtitle, true));
+        }
+        if let Some(dates) = &entry.dates {
+            if !details.is_empty() {
+                details.push_str(&run(" · ", false));
+            }
+            details.push_str(&run(dates, false));
+        }
+        if !details.is_empty() {
+            self.paragraph(None, &details);
+        }
+        if let Some(summary) = &entry.summary {
+            self.paragraph(None, &run(summary, false));
+        }
+        for bullet in &entry.bullets {
+            self.paragraph(Some("ListBullet"), &run(bullet, false));
+        }
+        if !entry.keywords.is_empty() {
+            let keywords = run("Keywords: ", true) + &run(&entry.keywords.join(", "), false);
+            self.paragraph(None, &keywords);
+        }
+    }
+}
+
+// A run of text. Line breaks in the source become breaks rather than being swallowed.
+fn run(text: &str, italic: bool) -> String {
+    let mut xml = String::from("<w:r>");
+    if italic {
+        xml.push_str("<w:rPr><w:i/></w:rPr>");
+    }
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            xml.push_str("<w:br/>");
+        }
+        let _ = write!(xml, "<w:t xml:space=\"preserve\">{}</w:t>", escape(line));
+    }
+    xml.push_str("</w:r>");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outline::Section;
+    use std::io::{Cursor, Read};
+
+    fn part(docx: &[u8], name: &str) -> String {
+        let mut archive = zip::ZipArchive::new(Cursor::new(docx)).unwrap();
+        let mut contents = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        contents
+    }
+
+    #[test]
+    fn test_writes_headings_bullets_links_and_properties() {
+        let outline = Outline {
+            name: Some("Ada & Co".to_string()),
+            sections: vec![Section {
+                title: "Work",
+                entries: vec![Entry {
+                    title: "Acme".to_string(),
+                    url: Some("https://acme.example/?a=1&b=2".to_string()),
+                    bullets: vec!["Shipped <everything>".to_string()],
+                    ..Entry::default()
+                }],
+            }],
+            ..Outline::default()
+        };
+        let docx = render(&outline, "en-GB", "2024-05-06".parse().unwrap()).unwrap();
+
+        let document = part(&docx, "word/document.xml");
+        assert!(document.contains(
+            "<w:pStyle w:val=\"Title\"/></w:pPr><w:r><w:t xml:space=\"preserve\">Ada &amp; Co</w:t>"
+        ));
+        assert!(document.contains("<w:pStyle w:val=\"Heading1\"/>"));
+        assert!(document.contains("<w:hyperlink r:id=\"rIdLink1\""));
+        assert!(document.contains("<w:pStyle w:val=\"ListBullet\"/></w:pPr><w:r><w:t xml:space=\"preserve\">Shipped &lt;everything&gt;</w:t>"));
+
+        let rels = part(&docx, "word/_rels/document.xml.rels");
+        assert!(rels.contains("Id=\"rIdLink1\""));
+        assert!(
+            rels.contains("Target=\"https://acme.example/?a=1&amp;b=2\" TargetMode=\"External\"")
+        );
+
+        let core = part(&docx, "docProps/core.xml");
+        assert!(core.contains("<dc:title>Ada &amp; Co — Resume</dc:title>"));
+        assert!(core.contains("<dc:language>en-GB</dc:language>"));
+        assert!(core.contains(">2024-05-06T00:00:00Z</dcterms:modified>"));
+        assert!(part(&docx, "word/styles.xml").contains("<w:lang w:val=\"en-GB\"/>"));
+    }
+}
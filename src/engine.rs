@@ -0,0 +1,129 @@
+use crate::watch::files;
+use anyhow::{Context, Result, anyhow};
+use include_dir::{Dir, File, include_dir};
+use std::fs;
+use std::path::Path;
+use typst::foundations::Dict;
+use typst::layout::PagedDocument;
+use typst_as_lib::file_resolver::FileSystemResolver;
+use typst_as_lib::{TypstAsLibError, TypstEngine, TypstTemplateEngineBuilder};
+use typst_pdf::PdfOptions;
+
+// The templates and fonts that ship inside the binary.
+static TEMPLATES: Dir = include_dir!("$CARGO_MANIFEST_DIR/templates");
+static FONTS: Dir = include_dir!("$CARGO_MANIFEST_DIR/fonts");
+
+/// Compiles templates with fonts loaded once, and keeps Typst's memoized work between
+/// compilations, so each one after the first only redoes what actually changed.
+pub struct LiveEngine {
+    engine: TypstEngine,
+}
+
+impl LiveEngine {
+    /// Compiles templates straight from disk, for previews that follow template edits.
+    pub fn new(template_dir: &Path, font_dir: &Path) -> Result<Self> {
+        let fonts = font_files(font_dir)?;
+        let builder = TypstEngine::builder()
+            .fonts(fonts)
+            // Deliberately uncached, so template edits show up in the next compilation.
+            .add_file_resolver(FileSystemResolver::new(template_dir.to_path_buf()));
+        Ok(Self::build(builder))
+    }
+
+    /// Compiles the templates and fonts built into the binary.
+    pub fn builtin() -> Self {
+        let files = embedded(&TEMPLATES);
+        let (sources, binaries): (Vec<&File>, Vec<&File>) = files
+            .into_iter()
+            .partition(|file| file.path().extension().is_some_and(|ext| ext == "typ"));
+        let builder = TypstEngine::builder()
+            .fonts(embedded(&FONTS).into_iter().map(File::contents))
+            .with_static_source_file_resolver(
+                sources
+                    .into_iter()
+                    .filter_map(|file| Some((file.path().to_str()?, file.contents_utf8()?))),
+            )
+            .with_static_file_resolver(
+                binaries
+                    .into_iter()
+                    .filter_map(|file| Some((file.path().to_str()?, file.contents()))),
+            );
+        Self::build(builder)
+    }
+
+    fn build(builder: TypstTemplateEngineBuilder) -> Self {
+        let mut builder = builder.with_package_file_resolver();
+        // The default evicts everything after each compilation, which is what makes a
+        // one-off export cheap on memory but a rebuild slow.
+        builder.comemo_evict_max_age(Some(10));
+        Self {
+            engine: builder.build(),
+        }
+    }
+
+    pub fn compile(&self, entry: &str, inputs: Dict) -> Result<PagedDocument> {
+        self.engine
+            .compile_with_input(entry, inputs)
+            .output
+            .map_err(|e| match e {
+                TypstAsLibError::TypstSource(diagnostics) => anyhow!(
+                    "Typst rendering error: {}",
+                    diagnostics
+                        .iter()
+                        .map(|d| d.message.as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                ),
+                other => anyhow!("Typst rendering error: {}", other),
+            })
+    }
+}
+
+pub fn pdf(document: &PagedDocument) -> Result<Vec<u8>> {
+    typst_pdf::pdf(document, &PdfOptions::default())
+        .map_err(|e| anyhow!("PDF generation error: {:?}", e))
+}
+
+/// One SVG per page.
+pub fn svg(document: &PagedDocument) -> Vec<Vec<u8>> {
+    document
+        .pages
+        .iter()
+        .map(|page| typst_svg::svg(page).into_bytes())
+        .collect()
+}
+
+/// One PNG per page, at `ppi` pixels per inch.
+pub fn png(document: &PagedDocument, ppi: f32) -> Result<Vec<Vec<u8>>> {
+    document
+        .pages
+        .iter()
+        .map(|page| {
+            typst_render::render(page, ppi / 72.0)
+                .encode_png()
+                .map_err(|e| anyhow!("PNG encoding error: {}", e))
+        })
+        .collect()
+}
+
+// Every file in an embedded directory, at any depth.
+fn embedded(dir: &'static Dir<'static>) -> Vec<&'static File<'static>> {
+    let mut found: Vec<&File> = dir.files().collect();
+    found.extend(dir.dirs().flat_map(embedded));
+    found
+}
+
+fn font_files(dir: &Path) -> Result<Vec<Vec<u8>>> {
+    let mut fonts = Vec::new();
+    for path in files(dir) {
+        let is_font = path.extension().is_some_and(|ext| {
+            ["ttf", "otf", "ttc", "otc"]
+                .iter()
+                .any(|font| ext.eq_ignore_ascii_case(font))
+        });
+        if is_font {
+            fonts.push(fs::read(&path).with_context(|| format!("Could not read {:?}", path))?);
+        }
+    }
+    Ok(fonts)
+}
@@ -0,0 +1,250 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::fmt::Write;
+
+use crate::outline::{Entry, Outline, Section};
+use crate::package::escape;
+
+const STYLE: &str = include_str!("../style.css");
+
+// What the export adds to the shared stylesheet: the embedded faces and the classes only
+// this page uses.
+const EXPORT_STYLE: &str = r#"
+html {
+  font-family: "Inter", "Helvetica Neue", Arial, sans-serif;
+}
+
+h1,
+h2 {
+  font-family: "Source Serif 4", Georgia, serif;
+  font-weight: bold;
+}
+
+.item-summary {
+  margin-top: 3px;
+}
+
+.item-keywords {
+  display: flex;
+  flex-wrap: wrap;
+  gap: 4px;
+  margin-top: 5px;
+}
+
+.item-keywords li {
+  font-size: 1.2rem;
+  padding: 0 6px;
+  border: 1px solid #d8dee9;
+  border-radius: 3px;
+}
+"#;
+
+// (family, weight, file) for every face the export's stylesheet asks for.
+const FONTS: [(&str, u16, &[u8]); 2] = [
+    (
+        "Inter",
+        300,
+        include_bytes!("../fonts/Inter_18pt-Light.ttf"),
+    ),
+    (
+        "Source Serif 4",
+        700,
+        include_bytes!("../fonts/SourceSerif4-Bold.ttf"),
+    ),
+];
+
+/// Renders a standalone page: the stylesheet and fonts are inlined, so the file can be
+/// uploaded anywhere as is.
+pub fn render(outline: &Outline, lang: &str) -> String {
+    let mut html = String::new();
+    let title = outline.name.as_deref().unwrap_or("Resume");
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n<style>\n",
+        escape(lang),
+        escape(title)
+    );
+    for (family, weight, font) in FONTS {
+        let _ = writeln!(
+            html,
+            "@font-face {{\n  font-family: \"{}\";\n  font-weight: {};\n  \
+             src: url(data:font/ttf;base64,{}) format(\"truetype\");\n}}\n",
+            family,
+            weight,
+            STANDARD.encode(font)
+        );
+    }
+    html.push_str(&without_font_faces(STYLE));
+    html.push_str(EXPORT_STYLE);
+    html.push_str("</style>\n</head>\n<body>\n");
+    header(&mut html, outline);
+    html.push_str("<main>\n");
+    for section in &outline.sections {
+        self::section(&mut html, section);
+    }
+    html.push_str("</main>\n</body>\n</html>\n");
+    html
+}
+
+fn header(html: &mut String, outline: &Outline) {
+    html.push_str("<header class=\"header\">\n");
+    if let Some(name) = &outline.name {
+        let _ = writeln!(html, "<h1>{}</h1>", escape(name));
+    }
+    if let Some(label) = &outline.label {
+        let _ = writeln!(html, "<p class=\"secondary\">{}</p>", escape(label));
+    }
+    if !outline.contacts.is_empty() {
+        html.push_str("<address class=\"contact-info\">\n");
+        for contact in &outline.contacts {
+            let text = anchor(&contact.text, contact.href.as_deref());
+            let _ = writeln!(
+                html,
+                "<span class=\"contact-item\" title=\"{}\">{}</span>",
+                escape(&contact.kind),
+                text
+            );
+        }
+        html.push_str("</address>\n");
+    }
+    if let Some(summary) = &outline.summary {
+        let _ = writeln!(html, "<p class=\"summary\">{}</p>", escape(summary));
+    }
+    html.push_str("</header>\n");
+}
+
+fn section(html: &mut String, section: &Section) {
+    let _ = writeln!(
+        html,
+        "<section class=\"section {}\">\n<h2>{}</h2>",
+        section.title.to_lowercase(),
+        escape(section.title)
+    );
+    for entry in &section.entries {
+        self::entry(html, entry);
+    }
+    html.push_str("</section>\n");
+}
+
+fn entry(html: &mut String, entry: &Entry) {
+    html.push_str("<article class=\"item\">\n<div class=\"item-container\">\n");
+    let _ = writeln!(
+        html,
+        "<h3 class=\"item-title\">{}</h3>",
+        anchor(&entry.title, entry.url.as_deref())
+    );
+    if let Some(dates) = &entry.dates {
+        let _ = writeln!(html, "<span class=\"item-date\">{}</span>", escape(dates));
+    }
+    html.push_str("</div>\n");
+    if let Some(subtitle) = &entry.subtitle {
+        let _ = writeln!(html, "<p class=\"item-subtitle\">{}</p>", escape(subtitle));
+    }
+    if let Some(summary) = &entry.summary {
+        let _ = writeln!(html, "<p class=\"item-summary\">{}</p>", escape(summary));
+    }
+    if !entry.bullets.is_empty() {
+        html.push_str("<div class=\"item-details\">\n");
+        list(html, None, &entry.bullets);
+        html.push_str("</div>\n");
+    }
+    if !entry.keywords.is_empty() {
+        list(html, Some("item-keywords"), &entry.keywords);
+    }
+    html.push_str("</article>\n");
+}
+
+fn list(html: &mut String, class: Option<&str>, items: &[String]) {
+    match class {
+        Some(class) => {
+            let _ = writeln!(html, "<ul class=\"{}\">", class);
+        }
+        None => html.push_str("<ul>\n"),
+    }
+    for item in items {
+        let _ = writeln!(html, "<li>{}</li>", escape(item));
+    }
+    html.push_str("</ul>\n");
+}
+
+// Only links that open a page, a mail or a call; a `javascript:` URL stays plain text.
+fn anchor(text: &str, href: Option<&str>) -> String {
+    let safe = href.filter(|href| {
+        url::Url::parse(href)
+            .is_ok_and(|url| matches!(url.scheme(), "http" | "https" | "mailto" | "tel"))
+    });
+    match safe {
+        Some(href) => format!("<a href=\"{}\">{}</a>", escape(href), escape(text)),
+        None => escape(text),
+    }
+}
+
+// The shared stylesheet loads its faces from wherever it is deployed, which a standalone
+// page can't rely on.
+fn without_font_faces(css: &str) -> String {
+    let mut kept = String::new();
+    let mut rest = css;
+    while let Some(start) = rest.find("@font-face") {
+        kept.push_str(&rest[..start]);
+        rest = rest[start..]
+            .find('}')
+            .map_or("", |end| &rest[start + end + 1..]);
+    }
+    kept.push_str(rest);
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outline::Contact;
+
+    #[test]
+    fn test_renders_a_standalone_semantic_page() {
+        let outline = Outline {
+            name: Some("Ada <Lovelace>".to_string()),
+            contacts: vec![Contact {
+                kind: "Website".to_string(),
+                text: "example.com".to_string(),
+                href: Some("https://example.com/?a=1&b=\"2\"".to_string()),
+            }],
+            sections: vec![Section {
+                title: "Work",
+                entries: vec![Entry {
+                    title: "Acme".to_string(),
+                    dates: Some("Jan 2020 — Present".to_string()),
+                    bullets: vec!["Cut costs by 50% & more".to_string()],
+                    ..Entry::default()
+                }],
+            }],
+            ..Outline::default()
+        };
+        let html = render(&outline, "en");
+
+        assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"en\">"));
+        assert!(html.contains("<title>Ada &lt;Lovelace&gt;</title>"));
+        assert!(html.contains("src: url(data:font/ttf;base64,"));
+        assert!(!html.contains("url('/fonts/"));
+        assert!(html.contains("href=\"https://example.com/?a=1&amp;b=&quot;2&quot;\""));
+        assert!(html.contains("<section class=\"section work\">\n<h2>Work</h2>"));
+        assert!(html.contains("<span class=\"item-date\">Jan 2020 — Present</span>"));
+        assert!(html.contains("<li>Cut costs by 50% &amp; more</li>"));
+    }
+
+    #[test]
+    fn test_links_only_to_safe_schemes() {
+        assert_eq!(
+            anchor("Mail", Some("mailto:ada@example.com")),
+            "<a href=\"mailto:ada@example.com\">Mail</a>"
+        );
+        assert_eq!(
+            anchor("Call", Some("tel:+15551234")),
+            "<a href=\"tel:+15551234\">Call</a>"
+        );
+        assert_eq!(anchor("Site", Some("javascript:alert(1)")), "Site");
+        assert_eq!(anchor("Site", Some("JavaScript:alert(1)")), "Site");
+        assert_eq!(anchor("Site", Some("data:text/html,<b>")), "Site");
+    }
+}
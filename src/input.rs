@@ -0,0 +1,549 @@
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use regex::Regex;
+use serde_dhall::{NumKind, SimpleValue};
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::json_resume::Resume;
+use crate::loaders;
+use crate::merge;
+use crate::source_map::{Location, SourceMap};
+use crate::validate::{self, Diagnostic, Severity};
+
+static TOML_TABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\[\[?[A-Za-z0-9_.-]+\]\]?$").unwrap());
+static YAML_KEY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"^("[^"]*"|[A-Za-z_$][\w$-]*)\s*:(\s|$)"#).unwrap());
+static TOML_KEY: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"^("[^"]*"|[A-Za-z_][\w-]*)\s*="#).unwrap());
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InputFormat {
+    Json,
+    Dhall,
+    Yaml,
+    Toml,
+}
+
+impl fmt::Display for InputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Dhall => write!(f, "dhall"),
+            Self::Yaml => write!(f, "yaml"),
+            Self::Toml => write!(f, "toml"),
+        }
+    }
+}
+
+impl InputFormat {
+    /// Picks a format from the file extension, falling back to sniffing the contents.
+    pub fn detect(path: &Path, data: &str) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            Some(ext) if ext.eq_ignore_ascii_case("dhall") => Self::Dhall,
+            Some(ext) if ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml") => {
+                Self::Yaml
+            }
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => Self::Toml,
+            _ => Self::sniff(data),
+        }
+    }
+
+    // A JSON document has to open with an object or array, which is never valid Dhall
+    // at the top of a resume file (those start with `let`, `{=}`-completions or imports).
+    // YAML and TOML are recognised by their first meaningful line.
+    fn sniff(data: &str) -> Self {
+        let first_line = data
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .unwrap_or_default();
+        if TOML_TABLE.is_match(first_line) {
+            Self::Toml
+        } else if first_line.starts_with(['{', '[']) {
+            Self::Json
+        } else if first_line == "---" || YAML_KEY.is_match(first_line) {
+            Self::Yaml
+        } else if TOML_KEY.is_match(first_line) && !first_line.starts_with("let ") {
+            Self::Toml
+        } else {
+            Self::Dhall
+        }
+    }
+
+    /// Parses the raw document into an untyped tree, without checking it against the schema.
+    #[cfg(test)]
+    pub fn parse(self, data: &str) -> Result<Value> {
+        Ok(self.load(data).map(|(value, _)| value)?)
+    }
+
+    /// Parses the raw document and indexes where each value starts.
+    fn load(self, data: &str) -> Result<(Value, SourceMap), SyntaxError> {
+        match self {
+            Self::Json => {
+                let value = serde_json::from_str(data).map_err(|e| {
+                    let location = Location {
+                        line: e.line(),
+                        column: e.column(),
+                    };
+                    // serde_json appends the position to every message; it's kept separately.
+                    let suffix = format!(" at line {} column {}", e.line(), e.column());
+                    let message = e.to_string();
+                    SyntaxError {
+                        message: message
+                            .strip_suffix(&suffix)
+                            .unwrap_or(&message)
+                            .to_string(),
+                        location: (e.line() > 0).then_some(location),
+                    }
+                })?;
+                Ok((value, SourceMap::json(data)))
+            }
+            // Dhall errors carry their own excerpt of the source, but no position to extract.
+            Self::Dhall => {
+                let value = serde_dhall::from_str(data)
+                    .parse::<SimpleValue>()
+                    .map_err(|e| SyntaxError {
+                        message: e.to_string(),
+                        location: None,
+                    })?;
+                Ok((dhall_to_json(value), SourceMap::dhall(data)))
+            }
+            Self::Yaml => loaders::yaml(data),
+            Self::Toml => loaders::toml(data),
+        }
+    }
+}
+
+// Dhall unions become externally tagged values, which is how serde lays out our enums in JSON.
+fn dhall_to_json(value: SimpleValue) -> Value {
+    match value {
+        SimpleValue::Num(NumKind::Bool(b)) => Value::Bool(b),
+        SimpleValue::Num(NumKind::Natural(n)) => n.into(),
+        SimpleValue::Num(NumKind::Integer(i)) => i.into(),
+        SimpleValue::Num(NumKind::Double(d)) => f64::from(d).into(),
+        SimpleValue::Text(s) => Value::String(s),
+        SimpleValue::Optional(None) => Value::Null,
+        SimpleValue::Optional(Some(inner)) => dhall_to_json(*inner),
+        SimpleValue::List(items) => Value::Array(items.into_iter().map(dhall_to_json).collect()),
+        SimpleValue::Record(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(k, v)| (k, dhall_to_json(v)))
+                .collect(),
+        ),
+        SimpleValue::Union(variant, None) => Value::String(variant),
+        SimpleValue::Union(variant, Some(inner)) => {
+            Value::Object([(variant, dhall_to_json(*inner))].into_iter().collect())
+        }
+    }
+}
+
+/// Why a document couldn't be parsed, and where the parser gave up if it says so.
+#[derive(Debug)]
+pub struct SyntaxError {
+    pub message: String,
+    pub location: Option<Location>,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some(location) => write!(f, "{} at {}", self.message, location),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for SyntaxError {}
+
+/// A document whose contents are not valid in its format.
+#[derive(Debug)]
+pub struct ParseError {
+    pub path: PathBuf,
+    pub format: InputFormat,
+    pub error: SyntaxError,
+}
+
+impl ParseError {
+    /// The failure as a finding, so reporters can describe a broken file like any other.
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic {
+            rule: "syntax",
+            severity: Severity::Error,
+            pointer: String::new(),
+            message: format!("not valid {}: {}", self.format, self.error.message),
+            location: self.error.location,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Could not parse {:?} as {}: {}",
+            self.path, self.format, self.error
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Whether a path argument is `-`, meaning stdin or stdout.
+pub fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// How a path argument reads in messages, with `-` named after the stream it stands for.
+pub fn display_path<'a>(path: &'a Path, stream: &'a str) -> &'a Path {
+    if is_stdio(path) {
+        Path::new(stream)
+    } else {
+        path
+    }
+}
+
+fn read_source(path: &Path) -> Result<String> {
+    if is_stdio(path) {
+        let mut data = String::new();
+        io::stdin()
+            .read_to_string(&mut data)
+            .context("Could not read the resume from stdin")?;
+        Ok(data)
+    } else {
+        fs::read_to_string(path).with_context(|| format!("Could not read {:?}", path))
+    }
+}
+
+/// A resume file that has been read and parsed, but not yet checked against the schema.
+pub struct Document {
+    pub path: PathBuf,
+    pub value: Value,
+    source_map: SourceMap,
+    // Files merged on top of `path`, in order.
+    overlays: Vec<PathBuf>,
+}
+
+impl Document {
+    /// Reads a document, honouring an explicit format when one is given.
+    /// A path of `-` reads stdin, where the format has to be detected from the contents.
+    pub fn read(path: &Path, format: Option<InputFormat>) -> Result<Self> {
+        let data = read_source(path)?;
+        Self::parse(display_path(path, "<stdin>"), &data, format)
+    }
+
+    /// Parses a document that has already been read from `path`.
+    pub fn parse(path: &Path, data: &str, format: Option<InputFormat>) -> Result<Self> {
+        let format = format.unwrap_or_else(|| InputFormat::detect(path, data));
+        let (value, source_map) = format.load(data).map_err(|error| ParseError {
+            path: path.to_path_buf(),
+            format,
+            error,
+        })?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            value,
+            source_map,
+            overlays: Vec::new(),
+        })
+    }
+
+    pub fn locate(&self, pointer: &str) -> Option<Location> {
+        self.source_map.locate(pointer)
+    }
+
+    /// The file a value comes from, which is an overlay if one set it.
+    pub fn origin(&self, pointer: &str) -> &Path {
+        match self.source_map.locate_from(pointer) {
+            Some((origin, _)) if origin > 0 => &self.overlays[origin - 1],
+            _ => &self.path,
+        }
+    }
+
+    /// Converts the document into a `Resume`, reporting every schema violation on failure.
+    /// With `strict`, unknown fields are violations as well.
+    pub fn resume(&self, strict: bool) -> Result<Resume> {
+        self.check(strict)?;
+        serde_json::from_value(self.value.clone())
+            .with_context(|| format!("{:?} does not match the Resume schema", self.path))
+    }
+
+    fn check(&self, strict: bool) -> Result<()> {
+        self.report(validate::validate(self, strict))
+    }
+
+    fn report(&self, diagnostics: Vec<Diagnostic>) -> Result<()> {
+        if !diagnostics.is_empty() {
+            let report: Vec<String> = diagnostics
+                .iter()
+                .map(|d| d.report(self.origin(&d.pointer)))
+                .collect();
+            bail!(
+                "{:?} does not match the Resume schema:\n{}",
+                self.path,
+                report.join("\n")
+            );
+        }
+        Ok(())
+    }
+
+    // Removal markers are not part of the schema, so they are checked as if absent.
+    fn check_layer(&mut self, strict: bool) -> Result<()> {
+        let mut value = self.value.clone();
+        merge::neutralize_markers(&mut value);
+        let original = std::mem::replace(&mut self.value, value);
+        let diagnostics = validate::validate_layer(self, strict);
+        self.value = original;
+        self.report(diagnostics)
+    }
+
+    /// Deep-merges `layer` on top of this document. Every value keeps the position it has
+    /// in the file that last set it.
+    pub fn overlay(&mut self, layer: Document) {
+        let base = self.value.clone();
+        merge::merge(&mut self.value, layer.value.clone());
+
+        let mut source_map = SourceMap::default();
+        for (pointer, origin, location) in self.source_map.entries() {
+            if let Some(target) = merge::follow(&base, &self.value, pointer) {
+                source_map.insert_from(origin, target, location);
+            }
+        }
+        self.overlays.push(layer.path);
+        for (pointer, _, location) in layer.source_map.entries() {
+            if let Some(target) = merge::follow(&layer.value, &self.value, pointer) {
+                source_map.insert_from(self.overlays.len(), target, location);
+            }
+        }
+        self.source_map = source_map;
+    }
+}
+
+/// Reads a base document and applies overlays in order. Each layer is checked on its own
+/// first, so problems are reported against the file and line they come from; fields a
+/// layer leaves out are only required of the merged result.
+pub fn read_layered(
+    base: &Path,
+    overlays: &[PathBuf],
+    format: Option<InputFormat>,
+    strict: bool,
+) -> Result<Document> {
+    // Stdin can only be read once; a second `-` would silently see an empty document.
+    if std::iter::once(base)
+        .chain(overlays.iter().map(PathBuf::as_path))
+        .filter(|path| is_stdio(path))
+        .count()
+        > 1
+    {
+        anyhow::bail!("Only one of the input and the overlays can be `-` for stdin");
+    }
+    let mut document = Document::read(base, format)?;
+    if overlays.is_empty() {
+        return Ok(document);
+    }
+    document.check_layer(strict)?;
+    for path in overlays {
+        let mut layer = Document::read(path, None)?;
+        layer.check_layer(strict)?;
+        document.overlay(layer);
+    }
+    Ok(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_by_extension() {
+        assert_eq!(
+            InputFormat::detect(Path::new("resume.json"), "let x = 1 in x"),
+            InputFormat::Json
+        );
+        assert_eq!(
+            InputFormat::detect(Path::new("resume.DHALL"), "{}"),
+            InputFormat::Dhall
+        );
+    }
+
+    #[test]
+    fn test_detect_by_content() {
+        assert_eq!(
+            InputFormat::detect(Path::new("resume"), "  \n{ \"basics\": null }"),
+            InputFormat::Json
+        );
+        assert_eq!(
+            InputFormat::detect(Path::new("resume.txt"), "let Resume = ./schema.dhall"),
+            InputFormat::Dhall
+        );
+    }
+
+    #[test]
+    fn test_detect_yaml_and_toml() {
+        assert_eq!(
+            InputFormat::detect(Path::new("cv.yml"), "{}"),
+            InputFormat::Yaml
+        );
+        assert_eq!(
+            InputFormat::detect(Path::new("cv"), "# my resume\nbasics:\n  name: Ada"),
+            InputFormat::Yaml
+        );
+        assert_eq!(
+            InputFormat::detect(Path::new("cv"), "[basics]\nname = \"Ada\""),
+            InputFormat::Toml
+        );
+        assert_eq!(
+            InputFormat::detect(
+                Path::new("cv"),
+                "let Resume = ./schema.dhall in Resume::{=}"
+            ),
+            InputFormat::Dhall
+        );
+    }
+
+    #[test]
+    fn test_parse_yaml_and_toml_agree_with_json() {
+        let json = InputFormat::Json
+            .parse(r#"{ "basics": { "name": "Ada" }, "work": [{ "location": "Remote", "highlights": ["a"] }] }"#)
+            .unwrap();
+        let yaml = InputFormat::Yaml
+            .parse("basics:\n  name: Ada\nwork:\n  - location: Remote\n    highlights: [a]\n")
+            .unwrap();
+        let toml = InputFormat::Toml
+            .parse("[basics]\nname = \"Ada\"\n\n[[work]]\nlocation = \"Remote\"\nhighlights = [\"a\"]\n")
+            .unwrap();
+        assert_eq!(json, yaml);
+        assert_eq!(json, toml);
+    }
+
+    #[test]
+    fn test_parse_json_and_dhall_agree() {
+        let json = InputFormat::Json
+            .parse(r#"{ "basics": { "name": "Ada" }, "work": [{ "location": "Remote" }] }"#)
+            .unwrap();
+        let dhall = InputFormat::Dhall
+            .parse(
+                r#"{ basics = Some { name = Some "Ada" }
+                   , work = Some [ { location = Some < Remote | OnSite : Text >.Remote } ]
+                   }"#,
+            )
+            .unwrap();
+        assert_eq!(json, dhall);
+    }
+
+    fn syntax_error(path: &str, data: &str) -> ParseError {
+        match Document::parse(Path::new(path), data, None) {
+            Ok(_) => panic!("{} parsed", path),
+            Err(err) => err.downcast::<ParseError>().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_syntax_errors_become_findings() {
+        let diagnostic =
+            syntax_error("resume.json", "{\n  \"basics\": { \"name\": \"Ada\", }\n}").diagnostic();
+        assert_eq!(diagnostic.rule, "syntax");
+        assert_eq!(diagnostic.message, "not valid json: trailing comma");
+        assert_eq!(
+            diagnostic.location,
+            Some(Location {
+                line: 2,
+                column: 30
+            })
+        );
+
+        let diagnostic = syntax_error("cv.toml", "[basics]\nname = \n").diagnostic();
+        assert_eq!(diagnostic.location.map(|l| l.line), Some(2));
+    }
+
+    fn layer(path: &str, json: &str) -> Document {
+        let mut document = Document::parse(Path::new(path), json, None).unwrap();
+        document.check_layer(false).unwrap();
+        document
+    }
+
+    #[test]
+    fn test_layers_may_be_partial() {
+        let mut document = layer(
+            "base.json",
+            r#"{
+  "basics": { "name": "Ada", "pronouns": { "primary": { "subject": "she", "object": "her",
+    "possessiveAdj": "hers", "possessivePronoun": "hers", "reflexive": "herself" },
+    "additional": [], "display": "she/her" } },
+  "meta": { "version": { "major": 1, "minor": 0, "patch": 0 } }
+}"#,
+        );
+        document.overlay(layer(
+            "patch.json",
+            r#"{ "meta": { "version": { "patch": 3 } } }"#,
+        ));
+        document.overlay(layer(
+            "pronouns.yaml",
+            "basics:\n  pronouns:\n    primary:\n      possessiveAdj: her\n",
+        ));
+        let resume = document.resume(false).unwrap();
+        assert_eq!(resume.meta.unwrap().version.unwrap().patch, 3);
+        let pronouns = resume.basics.unwrap().pronouns.unwrap();
+        assert_eq!(pronouns.primary.unwrap().possessive_adj, "her");
+    }
+
+    #[test]
+    #[cfg(feature = "schema")]
+    fn test_merged_findings_point_into_the_layer() {
+        let mut document = layer(
+            "base.json",
+            "{\n  \"work\": [{ \"name\": \"Acme\" }, { \"name\": \"Initech\" }]\n}",
+        );
+        let tailored = layer(
+            "tailored.yaml",
+            "basics:\n  pronouns:\n    display: she/her\nwork:\n  - name: Initech\n    startDate: 2020-12\n",
+        );
+        let pronouns = tailored.locate("/basics/pronouns");
+        document.overlay(tailored);
+        let diagnostics = validate::validate(&document, false);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "missing field `additional`");
+        assert_eq!(diagnostics[0].location, pronouns);
+        assert_eq!(
+            document.origin(&diagnostics[0].pointer),
+            Path::new("tailored.yaml")
+        );
+
+        // Items matched by name keep the position from whichever file set each field.
+        assert_eq!(
+            document.locate("/work/1/startDate"),
+            Some(Location {
+                line: 6,
+                column: 16
+            })
+        );
+        assert_eq!(
+            document.origin("/work/1/startDate"),
+            Path::new("tailored.yaml")
+        );
+        assert_eq!(
+            document.locate("/work/0/name"),
+            Some(Location {
+                line: 2,
+                column: 22
+            })
+        );
+        assert_eq!(document.origin("/work/0/name"), Path::new("base.json"));
+    }
+
+    #[test]
+    fn test_stdin_is_read_at_most_once() {
+        let overlays = [PathBuf::from("tailored.yaml"), PathBuf::from("-")];
+        let error = read_layered(Path::new("-"), &overlays, None, false)
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("stdin"), "{}", error);
+    }
+}
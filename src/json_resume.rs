@@ -1,55 +1,99 @@
 use std::collections::HashMap;
 use url::Url;
 
+pub use crate::date::{EndDate, PartialDate};
+
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Deserializer, Serialize};
+#[cfg(feature = "serde")]
+use std::sync::LazyLock;
 
+#[cfg(feature = "typst")]
+use derive_typst_intoval::IntoDict;
+#[cfg(feature = "typst")]
+use std::collections::BTreeMap;
 #[cfg(feature = "typst")]
 use typst::foundations::{Dict, IntoValue, Value};
 
-#[cfg(feature = "serde")]
-fn deserialize_iso8601<'de, D>(deserializer: D) -> Result<String, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let s = String::deserialize(deserializer)?;
-    // Basic regex for YYYY-MM-DD or YYYY-MM or YYYY
-    let re = regex::Regex::new(
-        r"^([1-2][0-9]{3}-[0-1][0-9]-[0-3][0-9]|[1-2][0-9]{3}-[0-1][0-9]|[1-2][0-9]{3})$",
-    )
-    .unwrap();
-
-    if re.is_match(&s) {
-        Ok(s)
-    } else {
-        Err(serde::de::Error::custom("Invalid ISO 8601 date format"))
+/// Where templates find custom fields.
+#[cfg(feature = "typst")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtraLayout {
+    /// Next to the known fields, which they never shadow.
+    Flat,
+    /// Under an `extra` key in each object that has any.
+    Namespaced,
+}
+
+/// Converts a resume into the dict templates receive, with custom fields laid out as asked.
+#[cfg(feature = "typst")]
+pub fn to_dict(resume: Resume, layout: ExtraLayout) -> Dict {
+    let dict = Dict::from(resume);
+    match layout {
+        ExtraLayout::Flat => flatten_extra(dict),
+        ExtraLayout::Namespaced => dict,
     }
 }
 
-#[cfg(feature = "serde")]
-fn deserialize_optional_iso8601<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let opt = Option::<String>::deserialize(deserializer)?;
-    match opt {
-        Some(s) => {
-            let re = regex::Regex::new(
-                r"^([1-2][0-9]{3}-[0-1][0-9]-[0-3][0-9]|[1-2][0-9]{3}-[0-1][0-9]|[1-2][0-9]{3})$",
-            )
-            .unwrap();
-            if re.is_match(&s) {
-                Ok(Some(s))
-            } else {
-                Err(serde::de::Error::custom("Invalid ISO 8601 date format"))
+// Moves every `extra` dict into its parent. Custom values themselves are left as written.
+#[cfg(feature = "typst")]
+fn flatten_extra(namespaced: Dict) -> Dict {
+    let mut dict = Dict::new();
+    let mut extra = Dict::new();
+    for (key, value) in namespaced {
+        match value {
+            Value::Dict(fields) if key.as_str() == "extra" => extra = fields,
+            value => {
+                dict.insert(key, flatten_value(value));
             }
         }
-        None => Ok(None),
+    }
+    for (key, value) in extra {
+        // Custom fields never shadow the ones the model knows about.
+        if !dict.contains(&key) {
+            dict.insert(key, value);
+        }
+    }
+    dict
+}
+
+#[cfg(feature = "typst")]
+fn flatten_value(value: Value) -> Value {
+    match value {
+        Value::Dict(dict) => Value::Dict(flatten_extra(dict)),
+        Value::Array(items) => Value::Array(items.into_iter().map(flatten_value).collect()),
+        value => value,
+    }
+}
+
+/// Converts an arbitrary JSON value, such as a custom field, into its Typst counterpart.
+#[cfg(feature = "typst")]
+pub fn json_to_typst(value: serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::None,
+        serde_json::Value::Bool(b) => b.into_value(),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into_value(),
+            None => n.as_f64().unwrap_or(f64::NAN).into_value(),
+        },
+        serde_json::Value::String(s) => s.into_value(),
+        serde_json::Value::Array(items) => items
+            .into_iter()
+            .map(json_to_typst)
+            .collect::<Vec<_>>()
+            .into_value(),
+        serde_json::Value::Object(map) => Value::Dict(
+            map.into_iter()
+                .map(|(k, v)| (k.into(), json_to_typst(v)))
+                .collect(),
+        ),
     }
 }
 
 #[cfg(feature = "serde")]
-fn deserialize_country_code<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+pub(crate) fn deserialize_country_code<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -69,15 +113,18 @@ where
 }
 
 #[cfg(feature = "serde")]
-fn deserialize_email<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+static EMAIL: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"^[^\s@]+@[^\s@]+\.[^\s@]+$").unwrap());
+
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_email<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
 where
     D: Deserializer<'de>,
 {
     let opt = Option::<String>::deserialize(deserializer)?;
     match opt {
         Some(s) => {
-            let email_re = regex::Regex::new(r"^[^\s@]+@[^\s@]+\.[^\s@]+$").unwrap();
-            if email_re.is_match(&s) {
+            if EMAIL.is_match(&s) {
                 Ok(Some(s))
             } else {
                 Err(serde::de::Error::custom("Invalid email format"))
@@ -91,8 +138,114 @@ where
 // ENUMS & SIMPLE TYPES
 // ============================================================================
 
+/// An absolute URL. Templates receive it as a plain string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct Link(pub Url);
+
+impl std::ops::Deref for Link {
+    type Target = Url;
+
+    fn deref(&self) -> &Url {
+        &self.0
+    }
+}
+
+impl From<Url> for Link {
+    fn from(url: Url) -> Self {
+        Self(url)
+    }
+}
+
+#[cfg(feature = "typst")]
+impl IntoValue for Link {
+    fn into_value(self) -> Value {
+        self.0.to_string().into_value()
+    }
+}
+
+// Keys the schema doesn't define, kept verbatim so templates can still use them.
+// (A plain comment: schemars would copy a doc comment onto every struct that flattens it.)
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct Extra(pub HashMap<String, serde_json::Value>);
+
+impl std::ops::Deref for Extra {
+    type Target = HashMap<String, serde_json::Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for Extra {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "typst")]
+impl IntoValue for Extra {
+    fn into_value(self) -> Value {
+        let sorted: BTreeMap<String, serde_json::Value> = self.0.into_iter().collect();
+        Value::Dict(
+            sorted
+                .into_iter()
+                .map(|(k, v)| (k.into(), json_to_typst(v)))
+                .collect(),
+        )
+    }
+}
+
+/// A bullet point, optionally tagged so tailored exports can leave it out.
+/// Templates receive only the text.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(untagged))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub enum Highlight {
+    Text(String),
+    Tagged {
+        text: String,
+        #[cfg_attr(feature = "serde", serde(default))]
+        tags: Vec<String>,
+    },
+}
+
+impl Highlight {
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Text(text) | Self::Tagged { text, .. } => text,
+        }
+    }
+
+    pub fn tags(&self) -> &[String] {
+        match self {
+            Self::Text(_) => &[],
+            Self::Tagged { tags, .. } => tags,
+        }
+    }
+}
+
+impl From<&str> for Highlight {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+#[cfg(feature = "typst")]
+impl IntoValue for Highlight {
+    fn into_value(self) -> Value {
+        match self {
+            Self::Text(text) | Self::Tagged { text, .. } => text.into_value(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
 pub enum LocationType {
     Remote,
     OnSite(String),
@@ -132,19 +285,30 @@ impl IntoValue for LocationType {
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
 pub enum Score {
-    GPA_Weighted { score: f64, scale: f64 },
+    #[cfg_attr(feature = "serde", serde(rename = "GPA_Weighted"))]
+    GpaWeighted {
+        score: f64,
+        scale: f64,
+    },
     Percentage(u32),
     PassFail(bool),
     LetterGrade(String),
-    Custom { score: String, scale: String },
+    Custom {
+        score: String,
+        scale: String,
+    },
+    /// Free text such as "4.0", as JSON Resume writes it.
+    #[cfg_attr(feature = "serde", serde(untagged))]
+    Text(String),
 }
 
 #[cfg(feature = "typst")]
 impl IntoValue for Score {
     fn into_value(self) -> Value {
         match self {
-            Self::GPA_Weighted { score, scale } => {
+            Self::GpaWeighted { score, scale } => {
                 let mut content = Dict::new();
                 content.insert("score".into(), score.into_value());
                 content.insert("scale".into(), scale.into_value());
@@ -175,21 +339,27 @@ impl IntoValue for Score {
                 d.insert("Custom".into(), Value::Dict(content));
                 Value::Dict(d)
             }
+            Self::Text(val) => val.into_value(),
         }
     }
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
 pub enum DegreeType {
     HighSchoolDiploma,
-    GED,
+    #[cfg_attr(feature = "serde", serde(rename = "GED"))]
+    Ged,
     AssociateDegree,
     BachelorDegree,
     MasterDegree,
-    MBA,
-    JD,
-    MD,
+    #[cfg_attr(feature = "serde", serde(rename = "MBA"))]
+    Mba,
+    #[cfg_attr(feature = "serde", serde(rename = "JD"))]
+    Jd,
+    #[cfg_attr(feature = "serde", serde(rename = "MD"))]
+    Md,
     PhD,
     PostDoc,
     Certificate,
@@ -208,6 +378,10 @@ impl IntoValue for DegreeType {
                 d.insert("Other".into(), val.into_value());
                 Value::Dict(d)
             }
+            Self::Ged => "GED".into_value(),
+            Self::Mba => "MBA".into_value(),
+            Self::Jd => "JD".into_value(),
+            Self::Md => "MD".into_value(),
             _ => format!("{:?}", self).into_value(),
         }
     }
@@ -215,6 +389,7 @@ impl IntoValue for DegreeType {
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
 pub enum SkillLevel {
     Beginner,
     Intermediate,
@@ -232,23 +407,31 @@ impl IntoValue for SkillLevel {
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
 pub enum FluencyLevel {
     Elementary,
     LimitedWorking,
     ProfessionalWorking,
     FullProfessional,
     NativeOrBilingual,
+    /// Free text such as "Native speaker", as JSON Resume writes it.
+    #[cfg_attr(feature = "serde", serde(untagged))]
+    Other(String),
 }
 
 #[cfg(feature = "typst")]
 impl IntoValue for FluencyLevel {
     fn into_value(self) -> Value {
-        format!("{:?}", self).into_value()
+        match self {
+            Self::Other(val) => val.into_value(),
+            _ => format!("{:?}", self).into_value(),
+        }
     }
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
 pub enum LanguageType {
     Afrikaans,
     Albanian,
@@ -370,6 +553,7 @@ impl IntoValue for LanguageType {
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
 pub enum ProjectType {
     Application,
     Website,
@@ -430,6 +614,7 @@ impl IntoValue for ProjectType {
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
 pub enum EmploymentType {
     FullTime,
     PartTime,
@@ -451,6 +636,7 @@ impl IntoValue for EmploymentType {
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
 pub enum NetworkType {
     GitHub,
     GitLab,
@@ -511,6 +697,8 @@ impl IntoValue for NetworkType {
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct SemVer {
     pub major: u32,
     pub minor: u32,
@@ -519,27 +707,13 @@ pub struct SemVer {
     pub build: Option<String>,
 }
 
-#[cfg(feature = "typst")]
-impl IntoValue for SemVer {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        dict.insert("major".into(), self.major.into_value());
-        dict.insert("minor".into(), self.minor.into_value());
-        dict.insert("patch".into(), self.patch.into_value());
-        if let Some(prerelease) = self.prerelease {
-            dict.insert("prerelease".into(), prerelease.into_value());
-        }
-        if let Some(build) = self.build {
-            dict.insert("build".into(), build.into_value());
-        }
-        Value::Dict(dict)
-    }
-}
-
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Address {
-    pub street: Option<String>,
+    #[cfg_attr(feature = "serde", serde(alias = "street"))]
+    pub address: Option<String>,
     pub city: Option<String>,
     pub region: Option<String>,
 
@@ -549,61 +723,26 @@ pub struct Address {
     #[cfg_attr(feature = "serde", serde(rename = "countryCode"))]
     #[cfg_attr(
         feature = "serde",
-        serde(deserialize_with = "deserialize_country_code")
+        serde(default, deserialize_with = "deserialize_country_code")
     )]
+    #[cfg_attr(feature = "schema", schemars(regex(pattern = r"^[A-Z]{2}$")))]
     pub country_code: Option<String>,
 }
 
-#[cfg(feature = "typst")]
-impl IntoValue for Address {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(street) = self.street {
-            dict.insert("street".into(), street.into_value());
-        }
-        if let Some(city) = self.city {
-            dict.insert("city".into(), city.into_value());
-        }
-        if let Some(region) = self.region {
-            dict.insert("region".into(), region.into_value());
-        }
-        if let Some(postal_code) = self.postal_code {
-            dict.insert("postalCode".into(), postal_code.into_value());
-        }
-        if let Some(country_code) = self.country_code {
-            dict.insert("countryCode".into(), country_code.into_value());
-        }
-        Value::Dict(dict)
-    }
-}
-
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Profile {
     pub network: Option<NetworkType>,
     pub username: Option<String>,
-    pub url: Option<Url>,
-}
-
-#[cfg(feature = "typst")]
-impl IntoValue for Profile {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(network) = self.network {
-            dict.insert("network".into(), network.into_value());
-        }
-        if let Some(username) = self.username {
-            dict.insert("username".into(), username.into_value());
-        }
-        if let Some(url) = self.url {
-            dict.insert("url".into(), url.to_string().into_value());
-        }
-        Value::Dict(dict)
-    }
+    pub url: Option<Link>,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct PronounSet {
     pub subject: String,
     pub object: String,
@@ -614,658 +753,318 @@ pub struct PronounSet {
     pub reflexive: String,
 }
 
-#[cfg(feature = "typst")]
-impl IntoValue for PronounSet {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        dict.insert("subject".into(), self.subject.into_value());
-        dict.insert("object".into(), self.object.into_value());
-        dict.insert("possessiveAdj".into(), self.possessive_adj.into_value());
-        dict.insert(
-            "possessivePronoun".into(),
-            self.possessive_pronoun.into_value(),
-        );
-        dict.insert("reflexive".into(), self.reflexive.into_value());
-        Value::Dict(dict)
-    }
-}
-
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Pronouns {
     pub primary: Option<PronounSet>,
     pub additional: Vec<PronounSet>,
     pub display: String,
 }
 
-#[cfg(feature = "typst")]
-impl IntoValue for Pronouns {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(primary) = self.primary {
-            dict.insert("primary".into(), primary.into_value());
-        }
-        let additional_array: Vec<_> = self
-            .additional
-            .into_iter()
-            .map(|p| p.into_value())
-            .collect();
-        dict.insert("additional".into(), additional_array.into_value());
-        dict.insert("display".into(), self.display.into_value());
-        Value::Dict(dict)
-    }
-}
-
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Basics {
     pub name: Option<String>,
     pub label: Option<String>,
     pub pronouns: Option<Pronouns>,
-    pub image: Option<Url>,
+    pub image: Option<Link>,
 
-    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_email"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, deserialize_with = "deserialize_email")
+    )]
+    #[cfg_attr(feature = "schema", schemars(email))]
     pub email: Option<String>,
 
     pub phone: Option<String>,
-    pub url: Option<Url>,
+    pub url: Option<Link>,
     pub summary: Option<String>,
     pub location: Option<Address>,
     pub profiles: Option<Vec<Profile>>,
 
     #[cfg_attr(feature = "serde", serde(flatten))]
-    pub additional_properties: HashMap<String, serde_json::Value>,
-}
-
-#[cfg(feature = "typst")]
-impl IntoValue for Basics {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(name) = self.name {
-            dict.insert("name".into(), name.into_value());
-        }
-        if let Some(label) = self.label {
-            dict.insert("label".into(), label.into_value());
-        }
-        if let Some(pronouns) = self.pronouns {
-            dict.insert("pronouns".into(), pronouns.into_value());
-        }
-        if let Some(image) = self.image {
-            dict.insert("image".into(), image.to_string().into_value());
-        }
-        if let Some(email) = self.email {
-            dict.insert("email".into(), email.into_value());
-        }
-        if let Some(phone) = self.phone {
-            dict.insert("phone".into(), phone.into_value());
-        }
-        if let Some(url) = self.url {
-            dict.insert("url".into(), url.to_string().into_value());
-        }
-        if let Some(summary) = self.summary {
-            dict.insert("summary".into(), summary.into_value());
-        }
-        if let Some(location) = self.location {
-            dict.insert("location".into(), location.into_value());
-        }
-        if let Some(profiles) = self.profiles {
-            let profiles_array: Vec<_> = profiles.into_iter().map(|p| p.into_value()).collect();
-            dict.insert("profiles".into(), profiles_array.into_value());
-        }
-        Value::Dict(dict)
-    }
+    #[cfg_attr(feature = "typst", rename("extra"))]
+    pub additional_properties: Extra,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Work {
     pub name: Option<String>,
     pub location: Option<LocationType>,
     pub description: Option<String>,
     pub position: Option<String>,
-    pub url: Option<Url>,
+    pub url: Option<Link>,
 
     #[cfg_attr(feature = "serde", serde(rename = "startDate"))]
-    #[cfg_attr(
-        feature = "serde",
-        serde(deserialize_with = "deserialize_optional_iso8601")
-    )]
-    pub start_date: Option<String>,
+    pub start_date: Option<PartialDate>,
 
     #[cfg_attr(feature = "serde", serde(rename = "endDate"))]
-    #[cfg_attr(
-        feature = "serde",
-        serde(deserialize_with = "deserialize_optional_iso8601")
-    )]
-    pub end_date: Option<String>,
+    pub end_date: Option<EndDate>,
 
     pub summary: Option<String>,
-    pub highlights: Option<Vec<String>>,
+    pub highlights: Option<Vec<Highlight>>,
     #[cfg_attr(feature = "serde", serde(rename = "employmentType"))]
     pub employment_type: Option<EmploymentType>,
 
-    #[cfg_attr(feature = "serde", serde(flatten))]
-    pub additional_properties: HashMap<String, serde_json::Value>,
-}
+    pub tags: Option<Vec<String>>,
 
-#[cfg(feature = "typst")]
-impl IntoValue for Work {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(name) = self.name {
-            dict.insert("name".into(), name.into_value());
-        }
-        if let Some(location) = self.location {
-            dict.insert("location".into(), location.into_value());
-        }
-        if let Some(description) = self.description {
-            dict.insert("description".into(), description.into_value());
-        }
-        if let Some(position) = self.position {
-            dict.insert("position".into(), position.into_value());
-        }
-        if let Some(url) = self.url {
-            dict.insert("url".into(), url.to_string().into_value());
-        }
-        if let Some(start_date) = self.start_date {
-            dict.insert("startDate".into(), start_date.into_value());
-        }
-        if let Some(end_date) = self.end_date {
-            dict.insert("endDate".into(), end_date.into_value());
-        }
-        if let Some(summary) = self.summary {
-            dict.insert("summary".into(), summary.into_value());
-        }
-        if let Some(highlights) = self.highlights {
-            dict.insert("highlights".into(), highlights.into_value());
-        }
-        if let Some(employment_type) = self.employment_type {
-            dict.insert("employmentType".into(), employment_type.into_value());
-        }
-        Value::Dict(dict)
-    }
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    #[cfg_attr(feature = "typst", rename("extra"))]
+    pub additional_properties: Extra,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Volunteer {
     pub organization: Option<String>,
     pub position: Option<String>,
-    pub url: Option<Url>,
+    pub url: Option<Link>,
     pub location: Option<LocationType>,
 
     #[cfg_attr(feature = "serde", serde(rename = "startDate"))]
-    #[cfg_attr(
-        feature = "serde",
-        serde(deserialize_with = "deserialize_optional_iso8601")
-    )]
-    pub start_date: Option<String>,
+    pub start_date: Option<PartialDate>,
 
     #[cfg_attr(feature = "serde", serde(rename = "endDate"))]
-    #[cfg_attr(
-        feature = "serde",
-        serde(deserialize_with = "deserialize_optional_iso8601")
-    )]
-    pub end_date: Option<String>,
+    pub end_date: Option<EndDate>,
 
     pub summary: Option<String>,
-    pub highlights: Option<Vec<String>>,
+    pub highlights: Option<Vec<Highlight>>,
 
-    #[cfg_attr(feature = "serde", serde(flatten))]
-    pub additional_properties: HashMap<String, serde_json::Value>,
-}
+    pub tags: Option<Vec<String>>,
 
-#[cfg(feature = "typst")]
-impl IntoValue for Volunteer {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(organization) = self.organization {
-            dict.insert("organization".into(), organization.into_value());
-        }
-        if let Some(position) = self.position {
-            dict.insert("position".into(), position.into_value());
-        }
-        if let Some(url) = self.url {
-            dict.insert("url".into(), url.to_string().into_value());
-        }
-        if let Some(location) = self.location {
-            dict.insert("location".into(), location.into_value());
-        }
-        if let Some(start_date) = self.start_date {
-            dict.insert("startDate".into(), start_date.into_value());
-        }
-        if let Some(end_date) = self.end_date {
-            dict.insert("endDate".into(), end_date.into_value());
-        }
-        if let Some(summary) = self.summary {
-            dict.insert("summary".into(), summary.into_value());
-        }
-        if let Some(highlights) = self.highlights {
-            dict.insert("highlights".into(), highlights.into_value());
-        }
-        Value::Dict(dict)
-    }
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    #[cfg_attr(feature = "typst", rename("extra"))]
+    pub additional_properties: Extra,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Education {
     pub institution: Option<String>,
-    pub url: Option<Url>,
+    pub url: Option<Link>,
     pub area: Option<String>,
 
     #[cfg_attr(feature = "serde", serde(rename = "studyType"))]
     pub study_type: Option<DegreeType>,
 
     #[cfg_attr(feature = "serde", serde(rename = "startDate"))]
-    #[cfg_attr(
-        feature = "serde",
-        serde(deserialize_with = "deserialize_optional_iso8601")
-    )]
-    pub start_date: Option<String>,
+    pub start_date: Option<PartialDate>,
 
     #[cfg_attr(feature = "serde", serde(rename = "endDate"))]
-    #[cfg_attr(
-        feature = "serde",
-        serde(deserialize_with = "deserialize_optional_iso8601")
-    )]
-    pub end_date: Option<String>,
+    pub end_date: Option<EndDate>,
 
     pub score: Option<Score>,
     pub courses: Option<Vec<String>>,
 
-    #[cfg_attr(feature = "serde", serde(flatten))]
-    pub additional_properties: HashMap<String, serde_json::Value>,
-}
+    pub tags: Option<Vec<String>>,
 
-#[cfg(feature = "typst")]
-impl IntoValue for Education {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(institution) = self.institution {
-            dict.insert("institution".into(), institution.into_value());
-        }
-        if let Some(url) = self.url {
-            dict.insert("url".into(), url.to_string().into_value());
-        }
-        if let Some(area) = self.area {
-            dict.insert("area".into(), area.into_value());
-        }
-        if let Some(study_type) = self.study_type {
-            dict.insert("studyType".into(), study_type.into_value());
-        }
-        if let Some(start_date) = self.start_date {
-            dict.insert("startDate".into(), start_date.into_value());
-        }
-        if let Some(end_date) = self.end_date {
-            dict.insert("endDate".into(), end_date.into_value());
-        }
-        if let Some(score) = self.score {
-            dict.insert("score".into(), score.into_value());
-        }
-        if let Some(courses) = self.courses {
-            dict.insert("courses".into(), courses.into_value());
-        }
-        Value::Dict(dict)
-    }
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    #[cfg_attr(feature = "typst", rename("extra"))]
+    pub additional_properties: Extra,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Award {
     pub title: Option<String>,
 
-    #[cfg_attr(
-        feature = "serde",
-        serde(deserialize_with = "deserialize_optional_iso8601")
-    )]
-    pub date: Option<String>,
+    pub date: Option<PartialDate>,
 
     pub awarder: Option<String>,
     pub summary: Option<String>,
 
-    #[cfg_attr(feature = "serde", serde(flatten))]
-    pub additional_properties: HashMap<String, serde_json::Value>,
-}
+    pub tags: Option<Vec<String>>,
 
-#[cfg(feature = "typst")]
-impl IntoValue for Award {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(title) = self.title {
-            dict.insert("title".into(), title.into_value());
-        }
-        if let Some(date) = self.date {
-            dict.insert("date".into(), date.into_value());
-        }
-        if let Some(awarder) = self.awarder {
-            dict.insert("awarder".into(), awarder.into_value());
-        }
-        if let Some(summary) = self.summary {
-            dict.insert("summary".into(), summary.into_value());
-        }
-        Value::Dict(dict)
-    }
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    #[cfg_attr(feature = "typst", rename("extra"))]
+    pub additional_properties: Extra,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Certificate {
     pub name: Option<String>,
 
-    #[cfg_attr(
-        feature = "serde",
-        serde(deserialize_with = "deserialize_optional_iso8601")
-    )]
-    pub date: Option<String>,
+    pub date: Option<PartialDate>,
 
-    pub url: Option<Url>,
+    pub url: Option<Link>,
     pub issuer: Option<String>,
 
     #[cfg_attr(feature = "serde", serde(rename = "expirationDate"))]
-    #[cfg_attr(
-        feature = "serde",
-        serde(deserialize_with = "deserialize_optional_iso8601")
-    )]
-    pub expiration_date: Option<String>,
+    pub expiration_date: Option<PartialDate>,
 
-    #[cfg_attr(feature = "serde", serde(flatten))]
-    pub additional_properties: HashMap<String, serde_json::Value>,
-}
+    pub tags: Option<Vec<String>>,
 
-#[cfg(feature = "typst")]
-impl IntoValue for Certificate {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(name) = self.name {
-            dict.insert("name".into(), name.into_value());
-        }
-        if let Some(date) = self.date {
-            dict.insert("date".into(), date.into_value());
-        }
-        if let Some(url) = self.url {
-            dict.insert("url".into(), url.to_string().into_value());
-        }
-        if let Some(issuer) = self.issuer {
-            dict.insert("issuer".into(), issuer.into_value());
-        }
-        if let Some(expiration_date) = self.expiration_date {
-            dict.insert("expirationDate".into(), expiration_date.into_value());
-        }
-        Value::Dict(dict)
-    }
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    #[cfg_attr(feature = "typst", rename("extra"))]
+    pub additional_properties: Extra,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Publication {
     pub name: Option<String>,
     pub publisher: Option<String>,
 
     #[cfg_attr(feature = "serde", serde(rename = "releaseDate"))]
-    #[cfg_attr(
-        feature = "serde",
-        serde(deserialize_with = "deserialize_optional_iso8601")
-    )]
-    pub release_date: Option<String>,
+    pub release_date: Option<PartialDate>,
 
-    pub url: Option<Url>,
+    pub url: Option<Link>,
     pub summary: Option<String>,
 
-    #[cfg_attr(feature = "serde", serde(flatten))]
-    pub additional_properties: HashMap<String, serde_json::Value>,
-}
+    pub tags: Option<Vec<String>>,
 
-#[cfg(feature = "typst")]
-impl IntoValue for Publication {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(name) = self.name {
-            dict.insert("name".into(), name.into_value());
-        }
-        if let Some(publisher) = self.publisher {
-            dict.insert("publisher".into(), publisher.into_value());
-        }
-        if let Some(release_date) = self.release_date {
-            dict.insert("releaseDate".into(), release_date.into_value());
-        }
-        if let Some(url) = self.url {
-            dict.insert("url".into(), url.to_string().into_value());
-        }
-        if let Some(summary) = self.summary {
-            dict.insert("summary".into(), summary.into_value());
-        }
-        Value::Dict(dict)
-    }
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    #[cfg_attr(feature = "typst", rename("extra"))]
+    pub additional_properties: Extra,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Skill {
     pub name: Option<String>,
     pub level: Option<SkillLevel>,
     pub keywords: Option<Vec<String>>,
 
-    #[cfg_attr(feature = "serde", serde(flatten))]
-    pub additional_properties: HashMap<String, serde_json::Value>,
-}
+    pub tags: Option<Vec<String>>,
 
-#[cfg(feature = "typst")]
-impl IntoValue for Skill {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(name) = self.name {
-            dict.insert("name".into(), name.into_value());
-        }
-        if let Some(level) = self.level {
-            dict.insert("level".into(), level.into_value());
-        }
-        if let Some(keywords) = self.keywords {
-            dict.insert("keywords".into(), keywords.into_value());
-        }
-        Value::Dict(dict)
-    }
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    #[cfg_attr(feature = "typst", rename("extra"))]
+    pub additional_properties: Extra,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Language {
     pub language: Option<LanguageType>,
     pub fluency: Option<FluencyLevel>,
 
-    #[cfg_attr(feature = "serde", serde(flatten))]
-    pub additional_properties: HashMap<String, serde_json::Value>,
-}
+    pub tags: Option<Vec<String>>,
 
-#[cfg(feature = "typst")]
-impl IntoValue for Language {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(language) = self.language {
-            dict.insert("language".into(), language.into_value());
-        }
-        if let Some(fluency) = self.fluency {
-            dict.insert("fluency".into(), fluency.into_value());
-        }
-        Value::Dict(dict)
-    }
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    #[cfg_attr(feature = "typst", rename("extra"))]
+    pub additional_properties: Extra,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Interest {
     pub name: Option<String>,
     pub keywords: Option<Vec<String>>,
 
-    #[cfg_attr(feature = "serde", serde(flatten))]
-    pub additional_properties: HashMap<String, serde_json::Value>,
-}
+    pub tags: Option<Vec<String>>,
 
-#[cfg(feature = "typst")]
-impl IntoValue for Interest {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(name) = self.name {
-            dict.insert("name".into(), name.into_value());
-        }
-        if let Some(keywords) = self.keywords {
-            dict.insert("keywords".into(), keywords.into_value());
-        }
-        Value::Dict(dict)
-    }
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    #[cfg_attr(feature = "typst", rename("extra"))]
+    pub additional_properties: Extra,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Reference {
     pub name: Option<String>,
     pub reference: Option<String>,
 
-    #[cfg_attr(feature = "serde", serde(flatten))]
-    pub additional_properties: HashMap<String, serde_json::Value>,
-}
+    pub tags: Option<Vec<String>>,
 
-#[cfg(feature = "typst")]
-impl IntoValue for Reference {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(name) = self.name {
-            dict.insert("name".into(), name.into_value());
-        }
-        if let Some(reference) = self.reference {
-            dict.insert("reference".into(), reference.into_value());
-        }
-        Value::Dict(dict)
-    }
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    #[cfg_attr(feature = "typst", rename("extra"))]
+    pub additional_properties: Extra,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Project {
     pub name: Option<String>,
     pub description: Option<String>,
-    pub highlights: Option<Vec<String>>,
+    pub highlights: Option<Vec<Highlight>>,
     pub keywords: Option<Vec<String>>,
 
     #[cfg_attr(feature = "serde", serde(rename = "startDate"))]
-    #[cfg_attr(
-        feature = "serde",
-        serde(deserialize_with = "deserialize_optional_iso8601")
-    )]
-    pub start_date: Option<String>,
+    pub start_date: Option<PartialDate>,
 
     #[cfg_attr(feature = "serde", serde(rename = "endDate"))]
-    #[cfg_attr(
-        feature = "serde",
-        serde(deserialize_with = "deserialize_optional_iso8601")
-    )]
-    pub end_date: Option<String>,
+    pub end_date: Option<EndDate>,
 
-    pub url: Option<Url>,
+    pub url: Option<Link>,
     pub roles: Option<Vec<String>>,
     pub entity: Option<String>,
 
     #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    #[cfg_attr(feature = "typst", rename("type"))]
     pub project_type: Option<ProjectType>,
 
-    #[cfg_attr(feature = "serde", serde(flatten))]
-    pub additional_properties: HashMap<String, serde_json::Value>,
-}
+    pub tags: Option<Vec<String>>,
 
-#[cfg(feature = "typst")]
-impl IntoValue for Project {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(name) = self.name {
-            dict.insert("name".into(), name.into_value());
-        }
-        if let Some(description) = self.description {
-            dict.insert("description".into(), description.into_value());
-        }
-        if let Some(highlights) = self.highlights {
-            dict.insert("highlights".into(), highlights.into_value());
-        }
-        if let Some(keywords) = self.keywords {
-            dict.insert("keywords".into(), keywords.into_value());
-        }
-        if let Some(start_date) = self.start_date {
-            dict.insert("startDate".into(), start_date.into_value());
-        }
-        if let Some(end_date) = self.end_date {
-            dict.insert("endDate".into(), end_date.into_value());
-        }
-        if let Some(url) = self.url {
-            dict.insert("url".into(), url.to_string().into_value());
-        }
-        if let Some(roles) = self.roles {
-            dict.insert("roles".into(), roles.into_value());
-        }
-        if let Some(entity) = self.entity {
-            dict.insert("entity".into(), entity.into_value());
-        }
-        if let Some(project_type) = self.project_type {
-            dict.insert("type".into(), project_type.into_value());
-        }
-        Value::Dict(dict)
-    }
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    #[cfg_attr(feature = "typst", rename("extra"))]
+    pub additional_properties: Extra,
 }
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct LastModified {
     pub date: String,
     pub time: String,
 }
 
-#[cfg(feature = "typst")]
-impl IntoValue for LastModified {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        dict.insert("date".into(), self.date.into_value());
-        dict.insert("time".into(), self.time.into_value());
-        Value::Dict(dict)
-    }
-}
-
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Meta {
-    pub canonical: Option<Url>,
+    pub canonical: Option<Link>,
     pub version: Option<SemVer>,
 
     #[cfg_attr(feature = "serde", serde(rename = "lastModified"))]
     pub last_modified: Option<LastModified>,
 
     #[cfg_attr(feature = "serde", serde(flatten))]
-    pub additional_properties: HashMap<String, serde_json::Value>,
-}
-
-#[cfg(feature = "typst")]
-impl IntoValue for Meta {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(canonical) = self.canonical {
-            dict.insert("canonical".into(), canonical.to_string().into_value());
-        }
-        if let Some(version) = self.version {
-            dict.insert("version".into(), version.into_value());
-        }
-        if let Some(last_modified) = self.last_modified {
-            dict.insert("lastModified".into(), last_modified.into_value());
-        }
-        Value::Dict(dict)
-    }
+    #[cfg_attr(feature = "typst", rename("extra"))]
+    pub additional_properties: Extra,
 }
 
 // ============================================================================
 // MAIN RESUME STRUCT
 // ============================================================================
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "typst", derive(IntoDict), rename("AsLowerCamelCase"))]
 pub struct Resume {
     #[cfg_attr(feature = "serde", serde(rename = "$schema"))]
-    pub schema: Option<Url>,
+    #[cfg_attr(feature = "typst", rename("$schema"))]
+    pub schema: Option<Link>,
 
     pub basics: Option<Basics>,
     pub work: Option<Vec<Work>>,
@@ -1282,178 +1081,66 @@ pub struct Resume {
     pub meta: Option<Meta>,
 
     #[cfg_attr(feature = "serde", serde(flatten))]
-    pub additional_properties: HashMap<String, serde_json::Value>,
+    #[cfg_attr(feature = "typst", rename("extra"))]
+    pub additional_properties: Extra,
 }
 
+// The derived dicts list every field. Templates expect unset ones to be absent, and custom
+// fields under `extra` only when there are any; `to_dict` flattens them if asked.
 #[cfg(feature = "typst")]
-impl IntoValue for Resume {
-    fn into_value(self) -> Value {
-        let mut dict = Dict::new();
-        if let Some(schema) = self.schema {
-            dict.insert("schema".into(), schema.to_string().into_value());
-        }
-        if let Some(basics) = self.basics {
-            dict.insert("basics".into(), basics.into_value());
-        }
-        if let Some(work) = self.work {
-            let work_array: Vec<_> = work.into_iter().map(|w| w.into_value()).collect();
-            dict.insert("work".into(), work_array.into_value());
-        }
-        if let Some(volunteer) = self.volunteer {
-            let volunteer_array: Vec<_> = volunteer.into_iter().map(|v| v.into_value()).collect();
-            dict.insert("volunteer".into(), volunteer_array.into_value());
-        }
-        if let Some(education) = self.education {
-            let education_array: Vec<_> = education.into_iter().map(|e| e.into_value()).collect();
-            dict.insert("education".into(), education_array.into_value());
-        }
-        if let Some(awards) = self.awards {
-            let awards_array: Vec<_> = awards.into_iter().map(|a| a.into_value()).collect();
-            dict.insert("awards".into(), awards_array.into_value());
-        }
-        if let Some(certificates) = self.certificates {
-            let certificates_array: Vec<_> =
-                certificates.into_iter().map(|c| c.into_value()).collect();
-            dict.insert("certificates".into(), certificates_array.into_value());
-        }
-        if let Some(publications) = self.publications {
-            let publications_array: Vec<_> =
-                publications.into_iter().map(|p| p.into_value()).collect();
-            dict.insert("publications".into(), publications_array.into_value());
-        }
-        if let Some(skills) = self.skills {
-            let skills_array: Vec<_> = skills.into_iter().map(|s| s.into_value()).collect();
-            dict.insert("skills".into(), skills_array.into_value());
-        }
-        if let Some(languages) = self.languages {
-            let languages_array: Vec<_> = languages.into_iter().map(|l| l.into_value()).collect();
-            dict.insert("languages".into(), languages_array.into_value());
-        }
-        if let Some(interests) = self.interests {
-            let interests_array: Vec<_> = interests.into_iter().map(|i| i.into_value()).collect();
-            dict.insert("interests".into(), interests_array.into_value());
-        }
-        if let Some(references) = self.references {
-            let references_array: Vec<_> = references.into_iter().map(|r| r.into_value()).collect();
-            dict.insert("references".into(), references_array.into_value());
-        }
-        if let Some(projects) = self.projects {
-            let projects_array: Vec<_> = projects.into_iter().map(|p| p.into_value()).collect();
-            dict.insert("projects".into(), projects_array.into_value());
-        }
-        if let Some(meta) = self.meta {
-            dict.insert("meta".into(), meta.into_value());
-        }
-        Value::Dict(dict)
-    }
-}
-
-impl Default for Resume {
-    fn default() -> Self {
-        Self {
-            schema: None,
-            basics: None,
-            work: None,
-            volunteer: None,
-            education: None,
-            awards: None,
-            certificates: None,
-            publications: None,
-            skills: None,
-            languages: None,
-            interests: None,
-            references: None,
-            projects: None,
-            meta: None,
-            additional_properties: HashMap::new(),
-        }
-    }
+fn finish_dict(derived: Dict) -> Dict {
+    derived
+        .into_iter()
+        .filter(|(key, value)| match value {
+            Value::None => false,
+            Value::Dict(fields) => key.as_str() != "extra" || !fields.is_empty(),
+            _ => true,
+        })
+        .collect()
 }
 
 #[cfg(feature = "typst")]
-impl From<Address> for Dict {
-    fn from(value: Address) -> Self {
-        if let Value::Dict(d) = value.into_value() {
-            d
-        } else {
-            Dict::new()
-        }
-    }
-}
+macro_rules! into_value_via_dict {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl From<$ty> for Dict {
+                fn from(value: $ty) -> Self {
+                    finish_dict(value.into_dict())
+                }
+            }
 
-#[cfg(feature = "typst")]
-impl From<Work> for Dict {
-    fn from(value: Work) -> Self {
-        if let Value::Dict(d) = value.into_value() {
-            d
-        } else {
-            Dict::new()
-        }
-    }
+            impl IntoValue for $ty {
+                fn into_value(self) -> Value {
+                    Value::Dict(self.into())
+                }
+            }
+        )*
+    };
 }
 
 #[cfg(feature = "typst")]
-impl From<Resume> for Dict {
-    fn from(value: Resume) -> Self {
-        let mut dict = Dict::new();
-        if let Some(schema) = value.schema {
-            dict.insert("schema".into(), schema.to_string().into_value());
-        }
-        if let Some(basics) = value.basics {
-            dict.insert("basics".into(), basics.into_value());
-        }
-        if let Some(work) = value.work {
-            let work_array: Vec<_> = work.into_iter().map(|w| w.into_value()).collect();
-            dict.insert("work".into(), work_array.into_value());
-        }
-        if let Some(volunteer) = value.volunteer {
-            let volunteer_array: Vec<_> = volunteer.into_iter().map(|v| v.into_value()).collect();
-            dict.insert("volunteer".into(), volunteer_array.into_value());
-        }
-        if let Some(education) = value.education {
-            let education_array: Vec<_> = education.into_iter().map(|e| e.into_value()).collect();
-            dict.insert("education".into(), education_array.into_value());
-        }
-        if let Some(awards) = value.awards {
-            let awards_array: Vec<_> = awards.into_iter().map(|a| a.into_value()).collect();
-            dict.insert("awards".into(), awards_array.into_value());
-        }
-        if let Some(certificates) = value.certificates {
-            let certificates_array: Vec<_> =
-                certificates.into_iter().map(|c| c.into_value()).collect();
-            dict.insert("certificates".into(), certificates_array.into_value());
-        }
-        if let Some(publications) = value.publications {
-            let publications_array: Vec<_> =
-                publications.into_iter().map(|p| p.into_value()).collect();
-            dict.insert("publications".into(), publications_array.into_value());
-        }
-        if let Some(skills) = value.skills {
-            let skills_array: Vec<_> = skills.into_iter().map(|s| s.into_value()).collect();
-            dict.insert("skills".into(), skills_array.into_value());
-        }
-        if let Some(languages) = value.languages {
-            let languages_array: Vec<_> = languages.into_iter().map(|l| l.into_value()).collect();
-            dict.insert("languages".into(), languages_array.into_value());
-        }
-        if let Some(interests) = value.interests {
-            let interests_array: Vec<_> = interests.into_iter().map(|i| i.into_value()).collect();
-            dict.insert("interests".into(), interests_array.into_value());
-        }
-        if let Some(references) = value.references {
-            let references_array: Vec<_> = references.into_iter().map(|r| r.into_value()).collect();
-            dict.insert("references".into(), references_array.into_value());
-        }
-        if let Some(projects) = value.projects {
-            let projects_array: Vec<_> = projects.into_iter().map(|p| p.into_value()).collect();
-            dict.insert("projects".into(), projects_array.into_value());
-        }
-        if let Some(meta) = value.meta {
-            dict.insert("meta".into(), meta.into_value());
-        }
-        dict
-    }
-}
+into_value_via_dict!(
+    SemVer,
+    Address,
+    Profile,
+    PronounSet,
+    Pronouns,
+    Basics,
+    Work,
+    Volunteer,
+    Education,
+    Award,
+    Certificate,
+    Publication,
+    Skill,
+    Language,
+    Interest,
+    Reference,
+    Project,
+    LastModified,
+    Meta,
+    Resume,
+);
 
 #[cfg(test)]
 mod tests {
@@ -1510,6 +1197,16 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_impossible_calendar_date_fails() {
+        for date in ["2023-19", "2023-02-30", "2023-19-39"] {
+            let json = format!(r#"{{ "certificates": [{{ "date": "{}" }}] }}"#, date);
+            let result: Result<Resume, _> = serde_json::from_str(&json);
+            assert!(result.is_err(), "{} should be rejected", date);
+        }
+    }
+
     #[test]
     #[cfg(feature = "serde")]
     fn test_invalid_country_code_fails() {
@@ -1553,7 +1250,7 @@ mod tests {
                 location: Some(Address {
                     city: Some("San Francisco".to_string()),
                     country_code: Some("US".to_string()),
-                    street: None,
+                    address: None,
                     postal_code: None,
                     region: None,
                 }),
@@ -1563,33 +1260,36 @@ mod tests {
                 url: None,
                 summary: None,
                 profiles: None,
-                additional_properties: HashMap::new(),
+                additional_properties: Extra::default(),
             }),
             work: Some(vec![Work {
                 name: Some("Tech Corp".to_string()),
                 position: Some("Software Engineer".to_string()),
-                start_date: Some("2020-01".to_string()),
-                end_date: Some("2023-12".to_string()),
+                start_date: Some("2020-01".parse().unwrap()),
+                end_date: Some("2023-12".parse().unwrap()),
                 location: None,
                 description: None,
                 url: None,
                 summary: None,
                 highlights: None,
                 employment_type: None,
-                additional_properties: HashMap::new(),
+                tags: None,
+                additional_properties: Extra::default(),
             }]),
             skills: Some(vec![
                 Skill {
                     name: Some("Rust".to_string()),
                     level: Some(SkillLevel::Expert),
                     keywords: Some(vec!["systems".to_string(), "performance".to_string()]),
-                    additional_properties: HashMap::new(),
+                    tags: None,
+                    additional_properties: Extra::default(),
                 },
                 Skill {
                     name: Some("Python".to_string()),
                     level: Some(SkillLevel::Advanced),
                     keywords: None,
-                    additional_properties: HashMap::new(),
+                    tags: None,
+                    additional_properties: Extra::default(),
                 },
             ]),
             ..Default::default()
@@ -1606,7 +1306,7 @@ mod tests {
     #[cfg(feature = "typst")]
     fn test_location_into_dict() {
         let address = Address {
-            street: Some("123 Main St".to_string()),
+            address: Some("123 Main St".to_string()),
             postal_code: Some("94102".to_string()),
             city: Some("San Francisco".to_string()),
             country_code: Some("US".to_string()),
@@ -1616,7 +1316,7 @@ mod tests {
         // Note: Using the trait implementation `into_value` which converts to Value::Dict
         let val = address.into_value();
         if let Value::Dict(dict) = val {
-            assert!(dict.get("street").is_ok());
+            assert!(dict.get("address").is_ok());
             assert!(dict.get("postalCode").is_ok());
             assert!(dict.get("city").is_ok());
             assert!(dict.get("countryCode").is_ok());
@@ -1632,18 +1332,22 @@ mod tests {
         let work = Work {
             name: Some("Acme Corp".to_string()),
             position: Some("Senior Developer".to_string()),
-            start_date: Some("2020-06".to_string()),
-            end_date: None,
+            start_date: Some("2020-06".parse().unwrap()),
+            end_date: Some(EndDate::Present),
             location: Some(LocationType::Remote),
             description: Some("Building cool things".to_string()),
             url: None,
             summary: Some("Led development team".to_string()),
             highlights: Some(vec![
-                "Improved performance by 50%".to_string(),
-                "Mentored 5 developers".to_string(),
+                "Improved performance by 50%".into(),
+                Highlight::Tagged {
+                    text: "Mentored 5 developers".to_string(),
+                    tags: vec!["management".to_string()],
+                },
             ]),
             employment_type: Some(EmploymentType::FullTime),
-            additional_properties: HashMap::new(),
+            tags: Some(vec!["backend".to_string()]),
+            additional_properties: Extra::default(),
         };
 
         let val = work.into_value();
@@ -1651,7 +1355,11 @@ mod tests {
             assert!(dict.get("name").is_ok());
             assert!(dict.get("position").is_ok());
             assert!(dict.get("startDate").is_ok());
-            assert!(dict.get("highlights").is_ok());
+            // Templates only ever see the text of a highlight.
+            assert_eq!(
+                dict.get("highlights").unwrap().clone(),
+                vec!["Improved performance by 50%", "Mentored 5 developers"].into_value()
+            );
         } else {
             panic!("Work should convert to a Dict");
         }
@@ -1691,4 +1399,199 @@ mod tests {
             panic!("Resume should convert to a Dict");
         }
     }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_reads_json_resume_documents() {
+        let resume: Resume = serde_json::from_str(include_str!("../resume.json")).unwrap();
+        let basics = resume.basics.unwrap();
+        assert_eq!(
+            basics.location.unwrap().address.as_deref(),
+            Some("2712 Broadway St")
+        );
+        assert!(matches!(
+            &resume.education.unwrap()[0].score,
+            Some(Score::Text(score)) if score == "4.0"
+        ));
+        assert!(matches!(
+            &resume.languages.unwrap()[0].fluency,
+            Some(FluencyLevel::Other(fluency)) if fluency == "Native speaker"
+        ));
+
+        // The model's own forms still take precedence over free text.
+        let language: Language =
+            serde_json::from_str(r#"{ "fluency": "NativeOrBilingual" }"#).unwrap();
+        assert!(matches!(
+            language.fluency,
+            Some(FluencyLevel::NativeOrBilingual)
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "typst"))]
+    fn test_additional_properties_reach_templates() {
+        let json = r#"{
+            "basics": { "name": "Ada", "x-clearance": { "level": "secret", "since": 2019 } },
+            "work": [{ "name": "Acme", "x-visa": true, "x-tags": ["oss", 1.5] }]
+        }"#;
+        let resume: Resume = serde_json::from_str(json).unwrap();
+
+        let dict = to_dict(resume.clone(), ExtraLayout::Flat);
+        let Value::Dict(basics) = dict.get("basics").unwrap().clone() else {
+            panic!("basics should be a Dict");
+        };
+        let Value::Dict(clearance) = basics.get("x-clearance").unwrap().clone() else {
+            panic!("custom objects should become a Dict");
+        };
+        assert_eq!(clearance.get("since").unwrap(), &2019i64.into_value());
+
+        let work = |dict: &Dict| match dict.get("work").unwrap() {
+            Value::Array(work) => match work.as_slice().first().unwrap() {
+                Value::Dict(work) => work.clone(),
+                _ => panic!("Work should convert to a Dict"),
+            },
+            _ => panic!("work should be an Array"),
+        };
+        assert_eq!(work(&dict).get("x-visa").unwrap(), &true.into_value());
+        assert!(work(&dict).get("extra").is_err());
+
+        let dict = to_dict(resume, ExtraLayout::Namespaced);
+        let work = work(&dict);
+        assert!(work.get("x-visa").is_err());
+        let Value::Dict(extra) = work.get("extra").unwrap().clone() else {
+            panic!("extra should be a Dict");
+        };
+        assert_eq!(extra.get("x-visa").unwrap(), &true.into_value());
+        assert_eq!(
+            extra.get("x-tags").unwrap(),
+            &vec!["oss".into_value(), 1.5.into_value()].into_value()
+        );
+    }
+
+    // Typst field names have to follow the serde renames, or templates silently miss data.
+    #[cfg(all(feature = "serde", feature = "typst"))]
+    fn assert_same_keys(json: &serde_json::Value, value: &Value, path: &str) {
+        match (json, value) {
+            (serde_json::Value::Object(map), Value::Dict(dict)) => {
+                let mut expected: Vec<&str> = map
+                    .iter()
+                    .filter(|(_, v)| !v.is_null())
+                    .map(|(k, _)| k.as_str())
+                    .collect();
+                let mut actual: Vec<&str> = dict.iter().map(|(k, _)| k.as_str()).collect();
+                expected.sort();
+                actual.sort();
+                assert_eq!(actual, expected, "keys differ at {}", path);
+                for (key, v) in map.iter().filter(|(_, v)| !v.is_null()) {
+                    let child = dict.get(key).unwrap();
+                    assert_same_keys(v, child, &format!("{}/{}", path, key));
+                }
+            }
+            (serde_json::Value::Array(items), Value::Array(values)) => {
+                for (i, (item, value)) in items.iter().zip(values.iter()).enumerate() {
+                    assert_same_keys(item, value, &format!("{}/{}", path, i));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Sets every field the model knows about, so a missing typst rename can't go unnoticed.
+    #[cfg(all(feature = "serde", feature = "typst"))]
+    const EVERY_FIELD: &str = r#"{
+        "$schema": "https://example.com/resume.schema.json",
+        "basics": {
+            "name": "Ada", "label": "Engineer", "image": "https://example.com/ada.png",
+            "email": "ada@example.com", "phone": "555", "url": "https://example.com",
+            "summary": "Hi",
+            "pronouns": {
+                "primary": { "subject": "she", "object": "her", "possessiveAdj": "her",
+                             "possessivePronoun": "hers", "reflexive": "herself" },
+                "additional": [], "display": "she/her"
+            },
+            "location": { "address": "1 Main St", "city": "London", "region": "LDN",
+                          "postalCode": "N1", "countryCode": "GB" },
+            "profiles": [{ "network": "GitHub", "username": "ada", "url": "https://github.com/ada" }]
+        },
+        "work": [{
+            "name": "Acme", "location": { "Hybrid": { "onSite": "London", "description": "2 days" } },
+            "description": "Widgets", "position": "Lead", "url": "https://acme.example",
+            "startDate": "2020-01", "endDate": "present", "summary": "Led", "highlights": ["Shipped"],
+            "employmentType": "FullTime", "tags": ["rust"]
+        }],
+        "volunteer": [{
+            "organization": "Club", "position": "Coach", "url": "https://club.example",
+            "location": "Remote", "startDate": "2019", "endDate": "2020", "summary": "Taught",
+            "highlights": ["Won"], "tags": ["people"]
+        }],
+        "education": [{
+            "institution": "Uni", "url": "https://uni.example", "area": "Maths",
+            "studyType": "BachelorDegree", "startDate": "2010", "endDate": "2013",
+            "score": { "GPA_Weighted": { "score": 3.9, "scale": 4.0 } }, "courses": ["Logic"],
+            "tags": ["maths"]
+        }],
+        "awards": [{ "title": "Prize", "date": "2015", "awarder": "Society", "summary": "For work",
+                     "tags": ["maths"] }],
+        "certificates": [{ "name": "Cert", "date": "2016", "url": "https://cert.example",
+                           "issuer": "Board", "expirationDate": "2026", "tags": ["ops"] }],
+        "publications": [{ "name": "Notes", "publisher": "Press", "releaseDate": "1843-10",
+                           "url": "https://press.example", "summary": "On engines", "tags": ["maths"] }],
+        "skills": [{ "name": "Rust", "level": "Expert", "keywords": ["traits"], "tags": ["rust"] }],
+        "languages": [{ "language": "English", "fluency": "NativeOrBilingual", "tags": ["people"] }],
+        "interests": [{ "name": "Chess", "keywords": ["openings"], "tags": ["games"] }],
+        "references": [{ "name": "Charles", "reference": "Great", "tags": ["people"] }],
+        "projects": [{
+            "name": "Engine", "description": "Analytical", "highlights": ["Designed"],
+            "keywords": ["gears"], "startDate": "1842", "endDate": "1843", "url": "https://engine.example",
+            "roles": ["Author"], "entity": "Self", "type": "Research", "tags": ["maths"]
+        }],
+        "meta": {
+            "canonical": "https://example.com/resume.json",
+            "version": { "major": 1, "minor": 2, "patch": 3, "prerelease": "rc.1", "build": "7" },
+            "lastModified": { "date": "2024-01-01", "time": "12:00" }
+        }
+    }"#;
+
+    // Every property name the schema lists, so the fixture can be checked for gaps.
+    #[cfg(all(feature = "serde", feature = "typst", feature = "schema"))]
+    fn schema_fields() -> Vec<String> {
+        let schema = crate::schema::json();
+        let objects = std::iter::once(&schema).chain(schema["$defs"].as_object().unwrap().values());
+        objects
+            .filter_map(|def| def["properties"].as_object())
+            .flat_map(|properties| properties.keys().cloned())
+            .collect()
+    }
+
+    #[cfg(all(feature = "serde", feature = "typst"))]
+    fn collect_keys(json: &serde_json::Value, keys: &mut Vec<String>) {
+        match json {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    keys.push(key.clone());
+                    collect_keys(value, keys);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                items.iter().for_each(|item| collect_keys(item, keys))
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "typst"))]
+    fn test_typst_keys_follow_serde_names() {
+        let resume: Resume = serde_json::from_str(EVERY_FIELD).unwrap();
+        let json = serde_json::to_value(&resume).unwrap();
+
+        let mut keys = Vec::new();
+        collect_keys(&json, &mut keys);
+        #[cfg(feature = "schema")]
+        for field in schema_fields() {
+            assert!(keys.contains(&field), "the fixture never sets `{}`", field);
+        }
+
+        assert_same_keys(&json, &resume.into_value(), "");
+    }
 }
@@ -0,0 +1,243 @@
+use serde_json::{Map, Value, json};
+
+use crate::date::{EndDate, PartialDate};
+use crate::json_resume::*;
+use crate::outline::{degree_name, language_name};
+
+/// Describes the resume's owner as a schema.org `Person`, ready to be embedded in a page
+/// as `<script type="application/ld+json">`.
+///
+/// Jobs become `OrganizationRole`s under `worksFor`, schools `OrganizationRole`s under
+/// `alumniOf`, and degrees and certificates `EducationalOccupationalCredential`s.
+pub fn person(resume: &Resume) -> Value {
+    let mut person = object([
+        ("@context", json!("https://schema.org")),
+        ("@type", json!("Person")),
+    ]);
+    if let Some(basics) = &resume.basics {
+        insert(&mut person, "name", &basics.name);
+        insert(&mut person, "jobTitle", &basics.label);
+        insert(&mut person, "description", &basics.summary);
+        insert(&mut person, "email", &basics.email);
+        insert(&mut person, "telephone", &basics.phone);
+        insert(&mut person, "url", &link(&basics.url));
+        insert(&mut person, "image", &link(&basics.image));
+        if let Some(location) = &basics.location {
+            let mut address = object([("@type", json!("PostalAddress"))]);
+            insert(&mut address, "streetAddress", &location.address);
+            insert(&mut address, "addressLocality", &location.city);
+            insert(&mut address, "addressRegion", &location.region);
+            insert(&mut address, "postalCode", &location.postal_code);
+            insert(&mut address, "addressCountry", &location.country_code);
+            if address.len() > 1 {
+                person.insert("address".into(), Value::Object(address));
+            }
+        }
+        let profiles: Vec<Value> = basics
+            .profiles
+            .iter()
+            .flatten()
+            .filter_map(|profile| Some(json!(profile.url.as_ref()?.to_string())))
+            .collect();
+        list(&mut person, "sameAs", profiles);
+    }
+
+    let jobs = items(&resume.work).map(|w| {
+        let mut organization = object([("@type", json!("Organization"))]);
+        insert(&mut organization, "name", &w.name);
+        insert(&mut organization, "url", &link(&w.url));
+        let mut role = role(organization, "worksFor", &w.start_date, &w.end_date);
+        insert(&mut role, "roleName", &w.position);
+        insert(&mut role, "description", &w.summary);
+        Value::Object(role)
+    });
+    list(&mut person, "worksFor", jobs.collect());
+
+    let mut credentials = Vec::new();
+    let schools = items(&resume.education).map(|e| {
+        let mut school = object([("@type", json!("EducationalOrganization"))]);
+        insert(&mut school, "name", &e.institution);
+        insert(&mut school, "url", &link(&e.url));
+
+        let degree = match (e.study_type.as_ref().map(degree_name), &e.area) {
+            (Some(degree), Some(area)) => Some(format!("{} in {}", degree, area)),
+            (degree, area) => degree.or_else(|| area.clone()),
+        };
+        if let Some(degree) = degree {
+            let mut credential = credential("degree", &Some(degree));
+            if let Some(end) = e.end_date.as_ref().and_then(EndDate::date) {
+                credential.insert("dateCreated".into(), json!(end.to_string()));
+            }
+            credential.insert("recognizedBy".into(), Value::Object(school.clone()));
+            credentials.push(Value::Object(credential));
+        }
+        Value::Object(role(school, "alumniOf", &e.start_date, &e.end_date))
+    });
+    list(&mut person, "alumniOf", schools.collect());
+
+    credentials.extend(items(&resume.certificates).map(|c| {
+        let mut credential = credential("certificate", &c.name);
+        insert(&mut credential, "url", &link(&c.url));
+        insert(&mut credential, "dateCreated", &date(&c.date));
+        insert(&mut credential, "expires", &date(&c.expiration_date));
+        if let Some(issuer) = &c.issuer {
+            credential.insert(
+                "recognizedBy".into(),
+                json!({ "@type": "Organization", "name": issuer }),
+            );
+        }
+        Value::Object(credential)
+    }));
+    list(&mut person, "hasCredential", credentials);
+
+    // schema.org keeps awards as plain text.
+    let awards = items(&resume.awards).filter_map(|a| {
+        let title = a.title.as_ref()?;
+        Some(json!(match &a.awarder {
+            Some(awarder) => format!("{}, {}", title, awarder),
+            None => title.clone(),
+        }))
+    });
+    list(&mut person, "award", awards.collect());
+
+    let mut topics: Vec<Value> = Vec::new();
+    for skill in items(&resume.skills) {
+        let names = skill.name.iter().chain(skill.keywords.iter().flatten());
+        for name in names {
+            if !topics.iter().any(|topic| topic == name.as_str()) {
+                topics.push(json!(name));
+            }
+        }
+    }
+    list(&mut person, "knowsAbout", topics);
+
+    let languages = items(&resume.languages).filter_map(|l| {
+        Some(json!({ "@type": "Language", "name": language_name(l.language.as_ref()?) }))
+    });
+    list(&mut person, "knowsLanguage", languages.collect());
+
+    Value::Object(person)
+}
+
+fn object<const N: usize>(entries: [(&str, Value); N]) -> Map<String, Value> {
+    entries
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect()
+}
+
+fn items<T>(items: &Option<Vec<T>>) -> impl Iterator<Item = &T> {
+    items.iter().flatten()
+}
+
+fn insert(object: &mut Map<String, Value>, key: &str, value: &Option<String>) {
+    if let Some(value) = value {
+        object.insert(key.to_string(), json!(value));
+    }
+}
+
+fn list(object: &mut Map<String, Value>, key: &str, values: Vec<Value>) {
+    if !values.is_empty() {
+        object.insert(key.to_string(), Value::Array(values));
+    }
+}
+
+fn link(url: &Option<Link>) -> Option<String> {
+    url.as_ref().map(|url| url.to_string())
+}
+
+fn date(date: &Option<PartialDate>) -> Option<String> {
+    date.as_ref().map(PartialDate::to_string)
+}
+
+// schema.org's role pattern: the role stands in for the organization and repeats the
+// property it qualifies.
+fn role(
+    organization: Map<String, Value>,
+    property: &str,
+    start: &Option<PartialDate>,
+    end: &Option<EndDate>,
+) -> Map<String, Value> {
+    let mut role = object([
+        ("@type", json!("OrganizationRole")),
+        (property, Value::Object(organization)),
+    ]);
+    insert(&mut role, "startDate", &date(start));
+    insert(
+        &mut role,
+        "endDate",
+        &date(&end.as_ref().and_then(EndDate::date)),
+    );
+    role
+}
+
+fn credential(category: &str, name: &Option<String>) -> Map<String, Value> {
+    let mut credential = object([
+        ("@type", json!("EducationalOccupationalCredential")),
+        ("credentialCategory", json!(category)),
+    ]);
+    insert(&mut credential, "name", name);
+    credential
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maps_resume_onto_schema_org() {
+        let resume: Resume = serde_json::from_value(json!({
+            "basics": {
+                "name": "Ada Lovelace",
+                "label": "Analyst",
+                "location": { "city": "London", "countryCode": "GB" },
+                "profiles": [{ "network": "GitHub", "url": "https://github.com/ada" }],
+            },
+            "work": [{
+                "name": "Analytical Engines",
+                "position": "Programmer",
+                "startDate": "1842-10",
+                "endDate": "present",
+            }],
+            "education": [{
+                "institution": "Home",
+                "studyType": "Diploma",
+                "area": "Mathematics",
+                "endDate": "1835",
+            }],
+            "certificates": [{ "name": "Notes", "issuer": "Royal Society", "date": "1843" }],
+            "awards": [{ "title": "First Programmer", "awarder": "Posterity" }],
+            "skills": [{ "name": "Mathematics", "keywords": ["Algorithms", "Mathematics"] }],
+            "languages": [{ "language": "French" }],
+        }))
+        .unwrap();
+        let person = person(&resume);
+
+        assert_eq!(person["@type"], "Person");
+        assert_eq!(person["jobTitle"], "Analyst");
+        assert_eq!(person["address"]["addressCountry"], "GB");
+        assert_eq!(person["sameAs"], json!(["https://github.com/ada"]));
+        assert_eq!(
+            person["worksFor"][0],
+            json!({
+                "@type": "OrganizationRole",
+                "worksFor": { "@type": "Organization", "name": "Analytical Engines" },
+                "startDate": "1842-10",
+                "roleName": "Programmer",
+            })
+        );
+        assert_eq!(
+            person["alumniOf"][0]["alumniOf"]["@type"],
+            "EducationalOrganization"
+        );
+        assert_eq!(person["hasCredential"][0]["name"], "Diploma in Mathematics");
+        assert_eq!(person["hasCredential"][0]["dateCreated"], "1835");
+        assert_eq!(
+            person["hasCredential"][1]["recognizedBy"]["name"],
+            "Royal Society"
+        );
+        assert_eq!(person["award"], json!(["First Programmer, Posterity"]));
+        assert_eq!(person["knowsAbout"], json!(["Mathematics", "Algorithms"]));
+        assert_eq!(person["knowsLanguage"][0]["name"], "French");
+    }
+}
@@ -0,0 +1,424 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use std::fmt;
+
+use crate::date::{EndDate, PartialDate};
+use crate::input::Document;
+use crate::json_resume::*;
+use crate::validate::{Diagnostic, Severity, locate};
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LintRule {
+    /// An entry ends before it starts
+    EndBeforeStart,
+    /// Two full-time positions overlap in time
+    OverlappingFullTime,
+    /// An entry starts after today
+    FutureStart,
+    /// A certificate's expiration date has passed
+    ExpiredCertificate,
+    /// A section is not listed newest first
+    UnsortedSection,
+    /// The time between two positions exceeds the configured threshold
+    EmploymentGap,
+}
+
+impl LintRule {
+    pub const ALL: [Self; 6] = [
+        Self::EndBeforeStart,
+        Self::OverlappingFullTime,
+        Self::FutureStart,
+        Self::ExpiredCertificate,
+        Self::UnsortedSection,
+        Self::EmploymentGap,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::EndBeforeStart => "end-before-start",
+            Self::OverlappingFullTime => "overlapping-full-time",
+            Self::FutureStart => "future-start",
+            Self::ExpiredCertificate => "expired-certificate",
+            Self::UnsortedSection => "unsorted-section",
+            Self::EmploymentGap => "employment-gap",
+        }
+    }
+}
+
+impl fmt::Display for LintRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+pub struct LintConfig {
+    pub rules: Vec<LintRule>,
+    /// Longest tolerated stretch between two positions, in whole months.
+    pub max_gap_months: u32,
+    pub today: PartialDate,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            rules: LintRule::ALL.to_vec(),
+            max_gap_months: 6,
+            today: PartialDate::today(),
+        }
+    }
+}
+
+/// Runs the enabled rules over a document that already satisfies the schema.
+pub fn lint(document: &Document, config: &LintConfig) -> Result<Vec<Diagnostic>> {
+    let resume = document.resume(false)?;
+    Ok(locate(document, check(&resume, config)))
+}
+
+// A dated entry of any section, flattened so the rules don't care where it came from.
+struct Entry<'a> {
+    pointer: String,
+    label: Option<&'a str>,
+    start: Option<PartialDate>,
+    end: Option<EndDate>,
+    full_time: bool,
+}
+
+impl Entry<'_> {
+    fn describe(&self) -> String {
+        match self.label {
+            Some(label) => format!("`{}` ({})", label, self.pointer),
+            None => self.pointer.clone(),
+        }
+    }
+
+    // Open-ended entries run until today.
+    fn months(&self, today: PartialDate) -> Option<(i32, i32)> {
+        let start = self.start?.month_index();
+        let end = self.end.and_then(|e| e.date()).unwrap_or(today);
+        Some((start, end.month_index()))
+    }
+}
+
+struct Section<'a> {
+    name: &'static str,
+    start_key: &'static str,
+    entries: Vec<Entry<'a>>,
+}
+
+fn section<'a, T>(
+    name: &'static str,
+    start_key: &'static str,
+    items: &'a Option<Vec<T>>,
+    entry: impl Fn(&'a T) -> (Option<&'a str>, Option<PartialDate>, Option<EndDate>),
+) -> Section<'a> {
+    let entries = items
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, item)| {
+            let (label, start, end) = entry(item);
+            Entry {
+                pointer: format!("/{}/{}", name, i),
+                label,
+                start,
+                end,
+                full_time: false,
+            }
+        })
+        .collect();
+    Section {
+        name,
+        start_key,
+        entries,
+    }
+}
+
+fn sections(resume: &Resume) -> Vec<Section<'_>> {
+    let mut work = section("work", "startDate", &resume.work, |w| {
+        (w.name.as_deref(), w.start_date, w.end_date)
+    });
+    for (entry, w) in work.entries.iter_mut().zip(resume.work.iter().flatten()) {
+        entry.full_time = matches!(w.employment_type, Some(EmploymentType::FullTime));
+    }
+    vec![
+        work,
+        section("volunteer", "startDate", &resume.volunteer, |v| {
+            (v.organization.as_deref(), v.start_date, v.end_date)
+        }),
+        section("education", "startDate", &resume.education, |e| {
+            (e.institution.as_deref(), e.start_date, e.end_date)
+        }),
+        section("projects", "startDate", &resume.projects, |p| {
+            (p.name.as_deref(), p.start_date, p.end_date)
+        }),
+        section("awards", "date", &resume.awards, |a| {
+            (a.title.as_deref(), a.date, None)
+        }),
+        section("certificates", "date", &resume.certificates, |c| {
+            (c.name.as_deref(), c.date, None)
+        }),
+        section("publications", "releaseDate", &resume.publications, |p| {
+            (p.name.as_deref(), p.release_date, None)
+        }),
+    ]
+}
+
+struct Linter<'a> {
+    config: &'a LintConfig,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linter<'_> {
+    fn warn(&mut self, rule: LintRule, pointer: String, message: String) {
+        if self.config.rules.contains(&rule) {
+            self.diagnostics.push(Diagnostic {
+                rule: rule.id(),
+                severity: Severity::Warning,
+                pointer,
+                message,
+                location: None,
+            });
+        }
+    }
+
+    fn end_before_start(&mut self, entry: &Entry) {
+        if let (Some(start), Some(EndDate::Date(end))) = (entry.start, entry.end)
+            && end.last_day() < start.first_day()
+        {
+            self.warn(
+                LintRule::EndBeforeStart,
+                format!("{}/endDate", entry.pointer),
+                format!("ends on {} but starts on {}", end, start),
+            );
+        }
+    }
+
+    fn future_start(&mut self, section: &Section, entry: &Entry) {
+        if let Some(start) = entry.start
+            && start.first_day() > self.config.today
+        {
+            self.warn(
+                LintRule::FutureStart,
+                format!("{}/{}", entry.pointer, section.start_key),
+                format!("{} is in the future", start),
+            );
+        }
+    }
+
+    // Sections are expected newest first, which is how every template renders them.
+    fn unsorted(&mut self, section: &Section) {
+        let dated: Vec<(&Entry, PartialDate)> = section
+            .entries
+            .iter()
+            .filter_map(|e| Some((e, e.start?)))
+            .collect();
+        for pair in dated.windows(2) {
+            let ((previous, before), (entry, after)) = (pair[0], pair[1]);
+            if after.first_day() > before.first_day() {
+                self.warn(
+                    LintRule::UnsortedSection,
+                    format!("{}/{}", entry.pointer, section.start_key),
+                    format!(
+                        "is more recent than {}; list {} newest first",
+                        previous.describe(),
+                        section.name
+                    ),
+                );
+            }
+        }
+    }
+
+    fn overlapping_full_time(&mut self, work: &Section) {
+        let today = self.config.today;
+        let roles: Vec<(&Entry, (i32, i32))> = work
+            .entries
+            .iter()
+            .filter(|e| e.full_time)
+            .filter_map(|e| Some((e, e.months(today)?)))
+            .collect();
+        for (j, (entry, (start, end))) in roles.iter().enumerate() {
+            // Sharing a single month is a normal handover, not an overlap.
+            let clash = roles[..j].iter().find(|(_, (s, e))| *s < *end && start < e);
+            if let Some((other, _)) = clash {
+                self.warn(
+                    LintRule::OverlappingFullTime,
+                    entry.pointer.clone(),
+                    format!("overlaps the full-time role {}", other.describe()),
+                );
+            }
+        }
+    }
+
+    fn employment_gap(&mut self, work: &Section) {
+        let today = self.config.today;
+        let mut roles: Vec<(&Entry, (i32, i32))> = work
+            .entries
+            .iter()
+            .filter_map(|e| Some((e, e.months(today)?)))
+            .collect();
+        roles.sort_by_key(|(_, (start, _))| *start);
+
+        let mut covered: Option<i32> = None;
+        for (entry, (start, end)) in roles {
+            if let Some(until) = covered {
+                let gap = start - until - 1;
+                if gap > self.config.max_gap_months as i32 {
+                    self.warn(
+                        LintRule::EmploymentGap,
+                        format!("{}/startDate", entry.pointer),
+                        format!("starts {} months after the previous position ended", gap),
+                    );
+                }
+            }
+            covered = Some(covered.map_or(end, |until| until.max(end)));
+        }
+    }
+
+    fn expired_certificates(&mut self, certificates: &[Certificate]) {
+        for (i, certificate) in certificates.iter().enumerate() {
+            if let Some(expiration) = certificate.expiration_date
+                && expiration.last_day() < self.config.today
+            {
+                self.warn(
+                    LintRule::ExpiredCertificate,
+                    format!("/certificates/{}/expirationDate", i),
+                    format!("expired on {}", expiration),
+                );
+            }
+        }
+    }
+}
+
+/// Applies the enabled rules to a parsed resume. Diagnostics come back without locations.
+pub fn check(resume: &Resume, config: &LintConfig) -> Vec<Diagnostic> {
+    let mut linter = Linter {
+        config,
+        diagnostics: Vec::new(),
+    };
+    let sections = sections(resume);
+
+    for section in &sections {
+        for entry in &section.entries {
+            linter.end_before_start(entry);
+            linter.future_start(section, entry);
+        }
+        linter.unsorted(section);
+    }
+    if let Some(work) = sections.iter().find(|section| section.name == "work") {
+        linter.overlapping_full_time(work);
+        linter.employment_gap(work);
+    }
+    linter.expired_certificates(resume.certificates.as_deref().unwrap_or_default());
+
+    linter.diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> LintConfig {
+        LintConfig {
+            today: "2024-06-15".parse().unwrap(),
+            ..LintConfig::default()
+        }
+    }
+
+    fn lint_json(resume: serde_json::Value, config: &LintConfig) -> Vec<(&'static str, String)> {
+        let resume: Resume = serde_json::from_value(resume).unwrap();
+        check(&resume, config)
+            .into_iter()
+            .map(|d| (d.rule, d.pointer))
+            .collect()
+    }
+
+    #[test]
+    fn test_reports_each_rule() {
+        let found = lint_json(
+            serde_json::json!({
+                "work": [
+                    { "name": "Next", "startDate": "2025-01", "employmentType": "FullTime" },
+                    { "name": "B", "startDate": "2021-01", "endDate": "2022-06", "employmentType": "FullTime" },
+                    { "name": "A", "startDate": "2022-01", "endDate": "2023-01", "employmentType": "FullTime" },
+                ],
+                "education": [{ "startDate": "2015-09", "endDate": "2012-06" }],
+                "certificates": [{ "name": "Cloud", "date": "2020", "expirationDate": "2023-12" }],
+            }),
+            &config(),
+        );
+        assert_eq!(
+            found,
+            vec![
+                ("future-start", "/work/0/startDate".to_string()),
+                ("unsorted-section", "/work/2/startDate".to_string()),
+                ("end-before-start", "/education/0/endDate".to_string()),
+                ("overlapping-full-time", "/work/2".to_string()),
+                ("employment-gap", "/work/0/startDate".to_string()),
+                (
+                    "expired-certificate",
+                    "/certificates/0/expirationDate".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rules_can_be_disabled() {
+        let resume = serde_json::json!({
+            "work": [
+                { "startDate": "2020-01", "endDate": "present" },
+                { "startDate": "2021-01", "endDate": "2019-01" },
+            ],
+        });
+        assert_eq!(lint_json(resume.clone(), &config()).len(), 2);
+
+        let config = LintConfig {
+            rules: vec![LintRule::EndBeforeStart],
+            ..config()
+        };
+        assert_eq!(
+            lint_json(resume, &config),
+            vec![("end-before-start", "/work/1/endDate".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_gap_threshold_and_handover() {
+        let resume = serde_json::json!({
+            "work": [
+                { "startDate": "2023-01", "employmentType": "FullTime" },
+                { "startDate": "2020-01", "endDate": "2022-03", "employmentType": "FullTime" },
+                { "startDate": "2018-01", "endDate": "2020-01", "employmentType": "FullTime" },
+            ],
+        });
+        let config = LintConfig {
+            max_gap_months: 9,
+            ..config()
+        };
+        assert!(lint_json(resume.clone(), &config).is_empty());
+
+        let config = LintConfig {
+            max_gap_months: 3,
+            ..config
+        };
+        assert_eq!(
+            lint_json(resume, &config),
+            vec![("employment-gap", "/work/0/startDate".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_findings_come_in_file_order() {
+        let source = r#"{
+  "certificates": [{ "name": "Cloud", "date": "2020", "expirationDate": "2023-12" }],
+  "work": [{ "startDate": "2021-01", "endDate": "2019-01" }]
+}"#;
+        let document = Document::parse(std::path::Path::new("resume.json"), source, None).unwrap();
+        let found: Vec<&str> = lint(&document, &config())
+            .unwrap()
+            .iter()
+            .map(|d| d.rule)
+            .collect();
+        assert_eq!(found, ["expired-certificate", "end-before-start"]);
+    }
+}
@@ -0,0 +1,499 @@
+//! Parsers for the hand-edited input formats. Each one builds the same untyped tree as the
+//! JSON path and records where every value starts, so diagnostics keep their positions.
+
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+use std::ops::Range;
+use toml::Spanned;
+use toml::de::{DeTable, DeValue};
+use yaml_rust2::Yaml;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+
+use crate::input::SyntaxError;
+#[cfg(feature = "schema")]
+use crate::schema;
+use crate::source_map::{Location, SourceMap, escape_token};
+
+pub fn yaml(source: &str) -> Result<(Value, SourceMap), SyntaxError> {
+    let mut builder = YamlBuilder::default();
+    Parser::new_from_str(source)
+        .load(&mut builder, false)
+        .map_err(|e| SyntaxError {
+            message: e.info().to_string(),
+            location: Some(location(*e.marker())),
+        })?;
+    if let Some(error) = builder.error {
+        return Err(error);
+    }
+    let value = builder.root.ok_or_else(|| SyntaxError {
+        message: "the YAML document is empty".to_string(),
+        location: None,
+    })?;
+    #[cfg(feature = "schema")]
+    let value = restore(value, &builder.plain);
+    Ok((value, builder.source_map))
+}
+
+// A collection that is still being filled, along with the pointer it lives at.
+struct Frame {
+    value: Value,
+    pointer: String,
+    anchor: usize,
+    key: Option<String>,
+}
+
+#[derive(Default)]
+struct YamlBuilder {
+    stack: Vec<Frame>,
+    // Anchored nodes with the pointer they were defined at.
+    anchors: Vec<(usize, Value, String)>,
+    root: Option<Value>,
+    source_map: SourceMap,
+    // The text of plain scalars that resolved to a number or boolean, by pointer.
+    plain: HashMap<String, String>,
+    error: Option<SyntaxError>,
+}
+
+impl YamlBuilder {
+    /// Returns the pointer for the next node, or `None` when that node is a mapping key.
+    fn next_pointer(&self) -> Option<String> {
+        match self.stack.last() {
+            None => Some(String::new()),
+            Some(Frame {
+                value: Value::Array(items),
+                pointer,
+                ..
+            }) => Some(format!("{}/{}", pointer, items.len())),
+            Some(Frame {
+                key: Some(key),
+                pointer,
+                ..
+            }) => Some(format!("{}/{}", pointer, escape_token(key))),
+            Some(_) => None,
+        }
+    }
+
+    fn start(&mut self, value: Value, anchor: usize, mark: Marker) {
+        let Some(pointer) = self.next_pointer() else {
+            self.error = Some(SyntaxError {
+                message: "mapping keys must be plain strings".to_string(),
+                location: Some(location(mark)),
+            });
+            return;
+        };
+        self.source_map.insert(pointer.clone(), location(mark));
+        self.stack.push(Frame {
+            value,
+            pointer,
+            anchor,
+            key: None,
+        });
+    }
+
+    fn finish(&mut self) {
+        if let Some(frame) = self.stack.pop() {
+            self.complete(frame.value, frame.anchor, frame.pointer);
+        }
+    }
+
+    fn scalar(&mut self, value: Value, anchor: usize, mark: Marker, plain: Option<String>) {
+        match self.next_pointer() {
+            Some(pointer) => {
+                if let Some(text) = plain.filter(|_| value.is_number() || value.is_boolean()) {
+                    self.plain.insert(pointer.clone(), text);
+                }
+                self.source_map.insert(pointer.clone(), location(mark));
+                self.complete(value, anchor, pointer);
+            }
+            // A key: keep its text as written, whatever type it would resolve to.
+            None => {
+                let key = match value {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                if let Some(frame) = self.stack.last_mut() {
+                    frame.key = Some(key);
+                }
+            }
+        }
+    }
+
+    fn complete(&mut self, value: Value, anchor: usize, pointer: String) {
+        if anchor != 0 {
+            self.anchors.push((anchor, value.clone(), pointer));
+        }
+        match self.stack.last_mut() {
+            None => self.root = Some(value),
+            Some(Frame {
+                value: Value::Array(items),
+                ..
+            }) => items.push(value),
+            Some(Frame {
+                value: Value::Object(map),
+                key,
+                ..
+            }) => {
+                if let Some(key) = key.take() {
+                    map.insert(key, value);
+                }
+            }
+            Some(_) => {}
+        }
+    }
+
+    // An alias repeats the anchored node, so its plain scalars keep their text there too.
+    fn copy_plain(&mut self, from: &str, to: &str) {
+        let copies: Vec<(String, String)> = self
+            .plain
+            .iter()
+            .filter_map(|(pointer, text)| {
+                let rest = pointer.strip_prefix(from)?;
+                (rest.is_empty() || rest.starts_with('/'))
+                    .then(|| (format!("{}{}", to, rest), text.clone()))
+            })
+            .collect();
+        self.plain.extend(copies);
+    }
+}
+
+impl MarkedEventReceiver for YamlBuilder {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        if self.error.is_some() {
+            return;
+        }
+        match event {
+            Event::MappingStart(anchor, _) => self.start(Value::Object(Map::new()), anchor, mark),
+            Event::SequenceStart(anchor, _) => self.start(Value::Array(Vec::new()), anchor, mark),
+            Event::MappingEnd | Event::SequenceEnd => self.finish(),
+            Event::Scalar(text, style, anchor, _) => {
+                if style == TScalarStyle::Plain {
+                    self.scalar(resolve_scalar(&text), anchor, mark, Some(text));
+                } else {
+                    self.scalar(Value::String(text), anchor, mark, None);
+                }
+            }
+            Event::Alias(id) => {
+                let anchored = self
+                    .anchors
+                    .iter()
+                    .rev()
+                    .find(|(anchor, ..)| *anchor == id)
+                    .map(|(_, value, pointer)| (value.clone(), pointer.clone()));
+                let Some((value, from)) = anchored else {
+                    self.scalar(Value::Null, 0, mark, None);
+                    return;
+                };
+                if let Some(to) = self.next_pointer() {
+                    self.copy_plain(&from, &to);
+                }
+                self.scalar(value, 0, mark, None);
+            }
+            _ => {}
+        }
+    }
+}
+
+// Plain scalars follow the YAML 1.2 core schema, like every other YAML loader.
+fn resolve_scalar(text: &str) -> Value {
+    match Yaml::from_str(text) {
+        Yaml::Integer(i) => i.into(),
+        Yaml::Real(r) => r
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map_or_else(|| Value::String(r), Value::Number),
+        Yaml::Boolean(b) => b.into(),
+        Yaml::Null => Value::Null,
+        _ => Value::String(text.to_string()),
+    }
+}
+
+// Plain scalars resolve without knowing where they go, so `startDate: 2020` or `postalCode:
+// 12345` become numbers. Where the schema only takes a string, they get their text back.
+#[cfg(feature = "schema")]
+fn restore(mut value: Value, plain: &HashMap<String, String>) -> Value {
+    let schema = schema::json();
+    let restore = Restore {
+        defs: &schema["$defs"],
+        plain,
+    };
+    restore.visit(&schema, &mut value, "");
+    value
+}
+
+#[cfg(feature = "schema")]
+struct Restore<'a> {
+    defs: &'a Value,
+    plain: &'a HashMap<String, String>,
+}
+
+#[cfg(feature = "schema")]
+impl<'a> Restore<'a> {
+    fn visit(&self, schema: &'a Value, value: &mut Value, pointer: &str) {
+        let forms = self.forms(schema);
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map.iter_mut() {
+                    let property = forms.iter().find_map(|form| form["properties"].get(key));
+                    if let Some(property) = property {
+                        let pointer = format!("{}/{}", pointer, escape_token(key));
+                        self.visit(property, child, &pointer);
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let Some(items_schema) = forms.iter().find_map(|form| form.get("items")) {
+                    for (i, item) in items.iter_mut().enumerate() {
+                        self.visit(items_schema, item, &format!("{}/{}", pointer, i));
+                    }
+                }
+            }
+            Value::Number(_) | Value::Bool(_) => {
+                let kinds: &[&str] = match value {
+                    Value::Bool(_) => &["boolean"],
+                    Value::Number(n) if n.is_f64() => &["number"],
+                    _ => &["integer", "number"],
+                };
+                let takes = |kind: &str| forms.iter().any(|form| has_type(form, kind));
+                if takes("string")
+                    && !kinds.iter().any(|kind| takes(kind))
+                    && let Some(text) = self.plain.get(pointer)
+                {
+                    *value = Value::String(text.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The schemas a value may match, with references and alternatives spelled out.
+    fn forms(&self, schema: &'a Value) -> Vec<&'a Value> {
+        let schema = match schema["$ref"].as_str() {
+            Some(reference) => &self.defs[reference.trim_start_matches("#/$defs/")],
+            None => schema,
+        };
+        match schema["anyOf"].as_array().or(schema["oneOf"].as_array()) {
+            Some(options) => options.iter().flat_map(|o| self.forms(o)).collect(),
+            None => vec![schema],
+        }
+    }
+}
+
+#[cfg(feature = "schema")]
+fn has_type(schema: &Value, kind: &str) -> bool {
+    match &schema["type"] {
+        Value::String(ty) => ty == kind,
+        Value::Array(types) => types.iter().any(|ty| ty == kind),
+        _ => false,
+    }
+}
+
+fn location(mark: Marker) -> Location {
+    Location {
+        line: mark.line(),
+        column: mark.col() + 1,
+    }
+}
+
+pub fn toml(source: &str) -> Result<(Value, SourceMap), SyntaxError> {
+    let lines = LineIndex::new(source);
+    let root = DeTable::parse(source).map_err(|e| SyntaxError {
+        message: e.message().to_string(),
+        location: e.span().map(|span| lines.locate(span.start)),
+    })?;
+    let mut converter = TomlConverter {
+        lines,
+        source_map: SourceMap::default(),
+    };
+    let span = root.span();
+    let value = converter.table(root.into_inner(), String::new(), span)?;
+    Ok((value, converter.source_map))
+}
+
+struct TomlConverter {
+    lines: LineIndex,
+    source_map: SourceMap,
+}
+
+impl TomlConverter {
+    fn table(
+        &mut self,
+        table: DeTable<'_>,
+        pointer: String,
+        span: Range<usize>,
+    ) -> Result<Value, SyntaxError> {
+        self.source_map
+            .insert(pointer.clone(), self.lines.locate(span.start));
+        let mut map = Map::new();
+        for (key, value) in table {
+            let key = key.into_inner().into_owned();
+            let child = format!("{}/{}", pointer, escape_token(&key));
+            map.insert(key, self.value(value, child)?);
+        }
+        Ok(Value::Object(map))
+    }
+
+    fn value(
+        &mut self,
+        value: Spanned<DeValue<'_>>,
+        pointer: String,
+    ) -> Result<Value, SyntaxError> {
+        let span = value.span();
+        let location = self.lines.locate(span.start);
+        self.source_map.insert(pointer.clone(), location);
+        Ok(match value.into_inner() {
+            DeValue::String(s) => Value::String(s.into_owned()),
+            DeValue::Integer(i) => i64::from_str_radix(i.as_str(), i.radix())
+                .map_or_else(|_| Value::String(i.as_str().to_string()), Value::from),
+            // JSON has no room for `nan` or `inf`.
+            DeValue::Float(f) => f
+                .as_str()
+                .replace('_', "")
+                .parse::<f64>()
+                .ok()
+                .and_then(Number::from_f64)
+                .map(Value::Number)
+                .ok_or_else(|| SyntaxError {
+                    message: format!("{} is not a finite number", f.as_str()),
+                    location: Some(location),
+                })?,
+            DeValue::Boolean(b) => b.into(),
+            // TOML dates are written as `1979-05-27`, exactly the strings the model expects.
+            DeValue::Datetime(d) => Value::String(d.to_string()),
+            DeValue::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .cloned()
+                    .enumerate()
+                    .map(|(i, item)| self.value(item, format!("{}/{}", pointer, i)))
+                    .collect::<Result<_, _>>()?,
+            ),
+            DeValue::Table(table) => self.table(table, pointer, span)?,
+        })
+    }
+}
+
+/// Turns byte offsets into one-based line and column numbers.
+struct LineIndex {
+    source: String,
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(source: &str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self {
+            source: source.to_string(),
+            starts,
+        }
+    }
+
+    fn locate(&self, offset: usize) -> Location {
+        let line = self.starts.partition_point(|&start| start <= offset);
+        let start = self.starts[line - 1];
+        let column = self
+            .source
+            .get(start..offset)
+            .map_or(0, |prefix| prefix.chars().count());
+        Location {
+            line,
+            column: column + 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_values_and_positions() {
+        let source = "basics:\n  name: Ada\n  # a comment\nwork:\n  - startDate: 2020-01\n    highlights:\n      - |\n        Shipped\n        things\n";
+        let (value, map) = yaml(source).unwrap();
+        assert_eq!(value["basics"]["name"], "Ada");
+        assert_eq!(value["work"][0]["startDate"], "2020-01");
+        assert_eq!(value["work"][0]["highlights"][0], "Shipped\nthings\n");
+        assert_eq!(
+            map.locate("/work/0/startDate"),
+            Some(Location {
+                line: 5,
+                column: 16
+            })
+        );
+    }
+
+    #[test]
+    fn test_yaml_scalars_resolve_like_json() {
+        let (value, _) = yaml("a: 12\nb: 1.5\nc: true\nd: ~\ne: '12'\nf: &x [1]\ng: *x\n").unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({ "a": 12, "b": 1.5, "c": true, "d": null, "e": "12", "f": [1], "g": [1] })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "schema")]
+    fn test_yaml_plain_scalars_stay_text_where_the_schema_wants_text() {
+        let source = "basics:\n  phone: 5551234\n  location:\n    postalCode: 01234\nwork:\n  - startDate: 2020\n    endDate: 2021\n    x-year: 2020\neducation:\n  - score:\n      Percentage: 90\nmeta:\n  version: { major: 1, minor: 0, patch: 2 }\n";
+        let (value, _) = yaml(source).unwrap();
+        assert_eq!(value["basics"]["phone"], "5551234");
+        assert_eq!(value["basics"]["location"]["postalCode"], "01234");
+        assert_eq!(value["work"][0]["startDate"], "2020");
+        assert_eq!(value["work"][0]["endDate"], "2021");
+        assert_eq!(value["work"][0]["x-year"], 2020);
+        assert_eq!(value["education"][0]["score"]["Percentage"], 90);
+        assert_eq!(value["meta"]["version"]["patch"], 2);
+    }
+
+    #[test]
+    #[cfg(feature = "schema")]
+    fn test_yaml_aliases_keep_the_text_of_plain_scalars() {
+        let source = "x-year: &year 2020
+x-range: &range { startDate: 2018 }
+work:
+  - startDate: *year
+  - *range
+";
+        let (value, _) = yaml(source).unwrap();
+        assert_eq!(value["work"][0]["startDate"], "2020");
+        assert_eq!(value["work"][1]["startDate"], "2018");
+        assert_eq!(value["x-year"], 2020);
+    }
+
+    #[test]
+    fn test_toml_values_and_positions() {
+        let source = "[basics]\nname = \"Ada\"\n\n[[work]]\nstartDate = \"2020-01\"\nendDate = 2021-06-30\nhighlights = [\"\"\"\nMulti\nline\"\"\"]\n";
+        let (value, map) = toml(source).unwrap();
+        assert_eq!(value["basics"]["name"], "Ada");
+        assert_eq!(value["work"][0]["endDate"], "2021-06-30");
+        assert_eq!(value["work"][0]["highlights"][0], "Multi\nline");
+        assert_eq!(
+            map.locate("/work/0/startDate"),
+            Some(Location {
+                line: 5,
+                column: 13
+            })
+        );
+    }
+
+    #[test]
+    fn test_toml_rejects_non_finite_numbers() {
+        let error = toml(
+            "[meta]
+x-ratio = nan
+",
+        )
+        .unwrap_err();
+        assert_eq!(error.message, "nan is not a finite number");
+        assert_eq!(
+            error.location,
+            Some(Location {
+                line: 2,
+                column: 11
+            })
+        );
+    }
+}
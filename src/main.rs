@@ -1,12 +1,53 @@
+use crate::config::{Config, Profile, Source};
+use crate::date::PartialDate;
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand};
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Instant;
+use typst::foundations::Dict;
+use typst::layout::PagedDocument;
 
+mod config;
+mod date;
+mod docx;
+mod engine;
+mod html;
+mod input;
 mod json_resume;
+mod jsonld;
+mod lint;
+mod loaders;
+mod markdown;
+mod merge;
+mod odt;
+mod outline;
+mod package;
+mod report;
 mod resumes;
-use crate::json_resume::Resume;
-use crate::resumes::Available;
+#[cfg(feature = "schema")]
+mod schema;
+mod serve;
+mod source_map;
+mod tags;
+mod text;
+mod validate;
+mod watch;
+use crate::engine::LiveEngine;
+use crate::input::{Document, InputFormat, ParseError, display_path, is_stdio, read_layered};
+use crate::json_resume::{ExtraLayout, Resume};
+use crate::lint::{LintConfig, LintRule};
+use crate::outline::Outline;
+use crate::report::OutputFormat;
+use crate::resumes::{Available, Format, PageRanges};
+use crate::serve::PreviewServer;
+use crate::tags::TagFilter;
+use crate::validate::{Diagnostic, Severity};
+use crate::watch::Watcher;
 
 #[derive(Parser)]
 #[command(name = "resume-bakery")]
@@ -25,46 +66,244 @@ enum Commands {
     },
     /// Validate the resume.json against the internal schema
     Validate {
+        /// Path to the resume file, or `-` for stdin
         #[arg(short, long, default_value = "resume.json")]
         input: PathBuf,
+
+        /// Parse the input as this format instead of detecting it
+        #[arg(long, value_enum)]
+        input_format: Option<InputFormat>,
+
+        /// How to print the findings
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        output_format: OutputFormat,
+
+        /// Report fields that are not part of the schema
+        #[arg(long)]
+        strict: bool,
     },
-    /// Export the resume to a specific format
-    Export {
-        /// Path to the resume.json file
+    /// Check the resume for chronological inconsistencies
+    Lint {
+        /// Path to the resume file, or `-` for stdin
         #[arg(short, long, default_value = "resume.json")]
         input: PathBuf,
 
-        /// Path for the output file
-        #[arg(short, long, default_value = "output.pdf")]
-        output: PathBuf,
+        /// Parse the input as this format instead of detecting it
+        #[arg(long, value_enum)]
+        input_format: Option<InputFormat>,
+
+        /// How to print the findings
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        output_format: OutputFormat,
+
+        /// Skip a rule; can be given several times
+        #[arg(long, value_enum)]
+        disable: Vec<LintRule>,
+
+        /// Longest gap between two positions, in months, before it is reported
+        #[arg(long, default_value_t = 6)]
+        max_gap_months: u32,
 
-        /// The typst template to use
+        /// Evaluate date rules as of this day instead of today
+        #[arg(long)]
+        today: Option<PartialDate>,
+
+        /// Fail on warnings as well as errors
+        #[arg(long)]
+        deny_warnings: bool,
+    },
+    /// Print the JSON Schema for resume files
+    #[cfg(feature = "schema")]
+    Schema {
+        /// Write the schema to this file instead of stdout
         #[arg(short, long)]
-        template: Available,
+        output: Option<PathBuf>,
+
+        /// Emit Dhall types and defaults instead of JSON Schema
+        #[arg(long)]
+        dhall: bool,
+    },
+    /// Export the resume to a specific format
+    Export {
+        #[command(flatten)]
+        document: DocumentArgs,
+
+        /// Path for the output file, or `-` for stdout [default: output.<format>]
+        #[arg(short, long)]
+        output: Option<PathBuf>,
 
         /// Output format
         #[arg(short, long, value_enum, default_value_t = Format::Pdf)]
         format: Format,
+
+        /// Line width for `--format text`; 0 turns wrapping off
+        #[arg(long, value_name = "COLUMNS", default_value_t = config::default_wrap())]
+        wrap: usize,
+
+        /// Resolution of `--format png`, in pixels per inch
+        #[arg(long, default_value_t = config::default_ppi(), value_parser = config::parse_ppi)]
+        ppi: f32,
+
+        /// Pages to write as images, such as `1` or `1,3-`; each goes to its own file
+        #[arg(long)]
+        pages: Option<PageRanges>,
+
+        /// Rebuild whenever the input, an overlay or a template changes
+        #[arg(long)]
+        watch: bool,
+
+        /// Templates to compile from while watching, instead of the built-in ones
+        #[arg(long, default_value = "templates", requires = "watch")]
+        template_dir: PathBuf,
+
+        /// Fonts to load once while watching
+        #[arg(long, default_value = "fonts", requires = "watch")]
+        font_dir: PathBuf,
+    },
+    /// Preview the resume in a browser, reloading it whenever a source changes
+    Serve {
+        #[command(flatten)]
+        document: DocumentArgs,
+
+        /// Address to listen on; keep the default to stay reachable from this machine only
+        #[arg(long, default_value = "127.0.0.1:7878")]
+        bind: String,
+
+        /// Templates to compile from
+        #[arg(long, default_value = "templates")]
+        template_dir: PathBuf,
+
+        /// Fonts to load once at startup
+        #[arg(long, default_value = "fonts")]
+        font_dir: PathBuf,
+    },
+    /// Run a build profile from the project config
+    Build {
+        /// Name of the profile to build, with every template and format it lists
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        profile: Option<String>,
+
+        /// Build every output of every profile, compiling them in parallel
+        #[arg(long)]
+        all: bool,
+
+        /// Path to the project config
+        #[arg(short, long, default_value = "resume.toml")]
+        config: PathBuf,
+    },
+    /// Merge partial resumes into a single JSON document
+    Merge {
+        /// Path to the base resume file, or `-` for stdin
+        #[arg(short, long, default_value = "resume.json")]
+        input: PathBuf,
+
+        /// Parse the input as this format instead of detecting it
+        #[arg(long, value_enum)]
+        input_format: Option<InputFormat>,
+
+        /// Partial resume merged on top of the input, in the order given
+        #[arg(long, required = true)]
+        overlay: Vec<PathBuf>,
+
+        /// Path for the merged resume, or `-` for stdout
+        #[arg(short, long, default_value = "-")]
+        output: PathBuf,
+
+        /// Report fields that are not part of the schema
+        #[arg(long)]
+        strict: bool,
     },
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
-enum Format {
-    Pdf,
+/// Everything that decides what goes into a document, shared by `export` and `serve`.
+#[derive(Args)]
+struct DocumentArgs {
+    /// Path to the resume file, or `-` for stdin
+    #[arg(short, long, default_value = "resume.json")]
+    input: PathBuf,
+
+    /// Parse the input as this format instead of detecting it
+    #[arg(long, value_enum)]
+    input_format: Option<InputFormat>,
+
+    /// Partial resume merged on top of the input; can be given several times
+    #[arg(long)]
+    overlay: Vec<PathBuf>,
+
+    /// The typst template to use
+    #[arg(short, long)]
+    template: Available,
+
+    /// Refuse to export if the resume contains fields that are not part of the schema
+    #[arg(long)]
+    strict: bool,
+
+    /// Give templates custom fields under an `extra` key instead of next to the known ones
+    #[arg(long)]
+    extra_namespace: bool,
+
+    /// Keep only tagged entries and highlights with this tag; can be given several times
+    #[arg(long)]
+    include_tag: Vec<String>,
+
+    /// Drop entries and highlights with this tag; can be given several times
+    #[arg(long)]
+    exclude_tag: Vec<String>,
+
+    /// Template parameter, available as `sys.inputs.params`; can be given several times
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_param)]
+    param: Vec<(String, String)>,
+}
+
+impl DocumentArgs {
+    fn into_profile(self, output: PathBuf, format: Format) -> Profile {
+        Profile {
+            input: self.input,
+            input_format: self.input_format,
+            overlays: self.overlay,
+            include_tags: self.include_tag,
+            exclude_tags: self.exclude_tag,
+            template: self.template,
+            templates: Vec::new(),
+            params: self
+                .param
+                .into_iter()
+                .map(|(key, value)| (key, value.into()))
+                .collect(),
+            output,
+            format,
+            formats: Vec::new(),
+            wrap: config::default_wrap(),
+            ppi: config::default_ppi(),
+            pages: PageRanges::default(),
+            strict: self.strict,
+            extra_namespace: self.extra_namespace,
+        }
+    }
 }
 
-macro_rules! bake_doc {
-    ($variant:expr, $inputs:expr, { $($arm:ident => $file:literal),* $(,)? }) => {
-        match $variant {
-            $(
-                Available::$arm => {
-                    typst_bake::document!($file).with_inputs($inputs)
-                }
-            )*
+// Progress messages move to stderr when stdout carries the document itself.
+macro_rules! status {
+    ($output:expr, $($arg:tt)*) => {
+        if is_stdio($output) {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
         }
     };
 }
 
+/// Writes a finished document to a file, or to stdout for `-`.
+fn write_output(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if is_stdio(path) {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(contents)?;
+        stdout.flush()
+    } else {
+        fs::write(path, contents)
+    }
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -75,42 +314,481 @@ fn main() -> Result<()> {
                 .with_context(|| format!("Failed to create {:?}", output))?;
             println!("Initialized new resume at {:?}", output);
         }
-        Commands::Validate { input } => {
-            let data = fs::read_to_string(&input)
-                .with_context(|| format!("Could not read {:?}", input))?;
-            let _: Resume = serde_dhall::from_str(&data)
-                .parse()
-                .with_context(|| "Validation failed: JSON does not match Resume schema")?;
-            println!("{:?}: Valid.", input);
+        Commands::Validate {
+            input,
+            input_format,
+            output_format,
+            strict,
+        } => {
+            let document = match Document::read(&input, input_format) {
+                Ok(document) => document,
+                Err(err) => return report_syntax(err, output_format),
+            };
+            let diagnostics = validate::validate(&document, strict);
+            finish(&document.path, output_format, &diagnostics, Severity::Error)?;
+        }
+        Commands::Lint {
+            input,
+            input_format,
+            output_format,
+            disable,
+            max_gap_months,
+            today,
+            deny_warnings,
+        } => {
+            let document = match Document::read(&input, input_format) {
+                Ok(document) => document,
+                Err(err) => return report_syntax(err, output_format),
+            };
+            let mut diagnostics = validate::validate(&document, false);
+            if diagnostics.is_empty() {
+                let config = LintConfig {
+                    rules: LintRule::ALL
+                        .into_iter()
+                        .filter(|rule| !disable.contains(rule))
+                        .collect(),
+                    max_gap_months,
+                    today: today.unwrap_or_else(PartialDate::today),
+                };
+                diagnostics = lint::lint(&document, &config)?;
+            }
+            let threshold = if deny_warnings {
+                Severity::Warning
+            } else {
+                Severity::Error
+            };
+            finish(&document.path, output_format, &diagnostics, threshold)?;
+        }
+        #[cfg(feature = "schema")]
+        Commands::Schema { output, dhall } => {
+            let schema = if dhall {
+                schema::dhall()
+            } else {
+                serde_json::to_string_pretty(&schema::json())? + "\n"
+            };
+            match output {
+                Some(output) => {
+                    fs::write(&output, schema)
+                        .with_context(|| format!("Failed to write {:?}", output))?;
+                    println!("Schema written to {:?}", output);
+                }
+                None => print!("{}", schema),
+            }
         }
         Commands::Export {
+            document,
+            output,
+            format,
+            wrap,
+            ppi,
+            pages,
+            watch,
+            template_dir,
+            font_dir,
+        } => {
+            let output =
+                output.unwrap_or_else(|| PathBuf::from(format!("output.{}", format.extension())));
+            let profile = Profile {
+                wrap,
+                ppi,
+                pages: pages.unwrap_or_default(),
+                ..document.into_profile(output, format)
+            };
+            if watch {
+                watch_export(&profile, &template_dir, &font_dir)?;
+            } else {
+                export(&profile)?;
+            }
+        }
+        Commands::Serve {
+            document,
+            bind,
+            template_dir,
+            font_dir,
+        } => {
+            let profile = document.into_profile(PathBuf::new(), Format::Pdf);
+            serve(&profile, &bind, &template_dir, &font_dir)?;
+        }
+        Commands::Build {
+            profile,
+            all,
+            config,
+        } => {
+            let config = Config::load(&config)?;
+            let profiles = match profile {
+                Some(name) if !all => vec![config.profile(&name)?],
+                _ => config.profiles.values().collect(),
+            };
+            build(&profiles)?;
+        }
+        Commands::Merge {
             input,
+            input_format,
+            overlay,
             output,
-            template,
-            format: _,
+            strict,
         } => {
-            // Read and parse
-            let data = fs::read_to_string(&input)
-                .with_context(|| format!("Could not read {:?}", input))?;
-            let resume: Resume = serde_dhall::from_str(&data)
-                .parse()
-                .with_context(|| "Failed to parse resume JSON")?;
+            let document = read_layered(&input, &overlay, input_format, strict)?;
+            document.resume(strict)?;
+            let merged = serde_json::to_string_pretty(&document.value)? + "\n";
+            let shown = display_path(&output, "<stdout>");
+            write_output(&output, merged.as_bytes())
+                .with_context(|| format!("Failed to write merged resume to {:?}", shown))?;
+            status!(
+                &output,
+                "Merged {} overlay(s) into {:?}",
+                overlay.len(),
+                shown
+            );
+        }
+    }
 
-            // Bake with Typst
-            let source_document = bake_doc!(template, resume, {
-                Basic => "template.typ",
-                // Modern => "modern.typ",
-            });
+    Ok(())
+}
+
+/// Reads a profile's input with its overlays merged on top.
+fn read(profile: &Profile) -> Result<Resume> {
+    read_layered(
+        &profile.input,
+        &profile.overlays,
+        profile.input_format,
+        profile.strict,
+    )?
+    .resume(profile.strict)
+}
+
+/// Runs the whole pipeline for one document: read, merge, filter and bake.
+fn export(profile: &Profile) -> Result<()> {
+    let document = produce(profile, read(profile)?, &LiveEngine::builtin())?;
+    save(profile, &document)
+}
+
+/// Reads a profile's sources afresh and compiles them with a live engine.
+fn render(profile: &Profile, engine: &LiveEngine) -> Result<PagedDocument> {
+    engine.compile(
+        profile.template.entry(),
+        typst_inputs(profile, read(profile)?),
+    )
+}
+
+fn watch_sources(profile: &Profile, template_dir: &Path) -> Result<Watcher> {
+    if is_stdio(&profile.input) {
+        anyhow::bail!("Cannot watch stdin; pass the resume as a file");
+    }
+    let mut watched = vec![profile.input.clone(), template_dir.to_path_buf()];
+    watched.extend(profile.overlays.iter().cloned());
+    Ok(Watcher::new(watched))
+}
+
+/// Exports once, then again on every change to the input, the overlays or the templates.
+/// Errors are reported without stopping, since the next save usually fixes them.
+fn watch_export(profile: &Profile, template_dir: &Path, font_dir: &Path) -> Result<()> {
+    if is_stdio(&profile.output) {
+        anyhow::bail!("--watch needs a file to write, not `-`");
+    }
+    let mut watcher = watch_sources(profile, template_dir)?;
+    let engine = LiveEngine::new(template_dir, font_dir)?;
+
+    loop {
+        let started = Instant::now();
+        let rebuilt = read(profile)
+            .and_then(|resume| produce(profile, resume, &engine))
+            .and_then(|document| save(profile, &document));
+        match rebuilt {
+            Ok(()) => println!("Rebuilt in {:.2?}", started.elapsed()),
+            Err(e) => eprintln!("Error: {:#}", e),
+        }
+        println!("Watching for changes...");
+        watcher.wait();
+    }
+}
+
+/// Serves a live preview, re-rendering on every change like `export --watch` does.
+fn serve(profile: &Profile, bind: &str, template_dir: &Path, font_dir: &Path) -> Result<()> {
+    let mut watcher = watch_sources(profile, template_dir)?;
+    let engine = LiveEngine::new(template_dir, font_dir)?;
+    let mut server = PreviewServer::bind(bind)?;
+    println!("Serving a live preview at http://{}/", server.address()?);
+    server.spawn();
+
+    loop {
+        let started = Instant::now();
+        let rendered = render(profile, &engine);
+        match &rendered {
+            Ok(_) => println!("Rendered in {:.2?}", started.elapsed()),
+            Err(e) => eprintln!("Error: {:#}", e),
+        }
+        server.publish(rendered);
+        watcher.wait();
+    }
+}
+
+/// Builds every output of the given profiles. Each distinct input is parsed once, and the
+/// compilations, which dominate the run time, are spread over the available cores.
+fn build(profiles: &[&Profile]) -> Result<()> {
+    let outputs: Vec<Profile> = profiles.iter().flat_map(|p| p.outputs()).collect();
+    if outputs.is_empty() {
+        anyhow::bail!("No profiles are defined");
+    }
 
-            println!("Baking resume using template: {}...", template);
-            let doc = source_document
-                .to_pdf()
-                .map_err(|e| anyhow::anyhow!("Typst rendering error: {:?}", e))?;
-            fs::write(&output, doc)
-                .with_context(|| format!("Failed to write PDF to {:?}", output))?;
-            println!("Success! Resume exported to {:?}", output);
+    // A source that can't be read fails every output built from it, but not the others.
+    let mut parsed: Vec<(Source, Result<Resume, String>)> = Vec::new();
+    let mut jobs = Vec::new();
+    let mut failures = Vec::new();
+    for profile in &outputs {
+        let source = profile.source();
+        let resume = match parsed.iter().find(|(s, _)| *s == source) {
+            Some((_, resume)) => resume.clone(),
+            None => {
+                let resume = read(profile).map_err(|e| format!("{:#}", e));
+                parsed.push((source, resume.clone()));
+                resume
+            }
+        };
+        match resume {
+            Ok(resume) => jobs.push((profile, resume)),
+            Err(e) => failures
+                .push(anyhow::anyhow!(e).context(format!("Failed to build {:?}", profile.output))),
         }
     }
 
+    // One engine for every job, so fonts and templates are only set up once.
+    let engine = LiveEngine::builtin();
+    let next = AtomicUsize::new(0);
+    let workers = thread::available_parallelism().map_or(1, usize::from);
+    failures.extend(thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers.min(jobs.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut failures = Vec::new();
+                    while let Some((profile, resume)) =
+                        jobs.get(next.fetch_add(1, Ordering::Relaxed))
+                    {
+                        // A panic fails this output instead of the whole run.
+                        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                            produce(profile, resume.clone(), &engine)
+                                .and_then(|doc| save(profile, &doc))
+                        }))
+                        .unwrap_or_else(|_| Err(anyhow::anyhow!("the build panicked")));
+                        if let Err(e) = result {
+                            failures
+                                .push(e.context(format!("Failed to build {:?}", profile.output)));
+                        }
+                    }
+                    failures
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| vec![anyhow::anyhow!("A build worker panicked")])
+            })
+            .collect::<Vec<_>>()
+    }));
+
+    for failure in &failures {
+        eprintln!("Error: {:#}", failure);
+    }
+    if !failures.is_empty() {
+        anyhow::bail!("{} of {} output(s) failed", failures.len(), outputs.len());
+    }
+    println!("Built {} output(s)", outputs.len());
+    Ok(())
+}
+
+/// What an export writes: a whole document, or images of some of its pages.
+enum Output {
+    Document(Vec<u8>),
+    Pages(Vec<(usize, Vec<u8>)>),
+}
+
+fn produce(profile: &Profile, resume: Resume, engine: &LiveEngine) -> Result<Output> {
+    if profile.format.uses_typst() {
+        status!(
+            &profile.output,
+            "Baking {:?} using template: {}...",
+            display_path(&profile.output, "<stdout>"),
+            profile.template
+        );
+        let document = engine.compile(profile.template.entry(), typst_inputs(profile, resume))?;
+        typst_output(profile, &document)
+    } else {
+        render_native(profile, resume).map(Output::Document)
+    }
+}
+
+fn tag_filter(profile: &Profile) -> TagFilter {
+    TagFilter {
+        include: profile.include_tags.clone(),
+        exclude: profile.exclude_tags.clone(),
+    }
+}
+
+/// Filters the resume and lays it out as the template's `sys.inputs`.
+fn typst_inputs(profile: &Profile, mut resume: Resume) -> Dict {
+    tag_filter(profile).apply(&mut resume);
+
+    let layout = if profile.extra_namespace {
+        ExtraLayout::Namespaced
+    } else {
+        ExtraLayout::Flat
+    };
+    let mut inputs = json_resume::to_dict(resume, layout);
+    if !profile.params.is_empty() {
+        let params = serde_json::Value::Object(profile.params.clone());
+        inputs.insert("params".into(), json_resume::json_to_typst(params));
+    }
+    inputs
+}
+
+/// Filters the resume and renders it without Typst.
+fn render_native(profile: &Profile, mut resume: Resume) -> Result<Vec<u8>> {
+    tag_filter(profile).apply(&mut resume);
+    let outline = Outline::from(&resume);
+    let lang = profile
+        .params
+        .get("lang")
+        .and_then(|lang| lang.as_str())
+        .unwrap_or("en");
+    Ok(match profile.format {
+        Format::Html => html::render(&outline, lang).into_bytes(),
+        Format::Markdown => markdown::render(&outline).into_bytes(),
+        Format::Text => text::render(&outline, profile.wrap).into_bytes(),
+        Format::Docx => docx::render(&outline, lang, PartialDate::today())?,
+        Format::Odt => odt::render(&outline, lang, PartialDate::today())?,
+        Format::JsonLd => {
+            (serde_json::to_string_pretty(&jsonld::person(&resume))? + "\n").into_bytes()
+        }
+        Format::Pdf | Format::Png | Format::Svg => {
+            unreachable!("{} is compiled by Typst", profile.format)
+        }
+    })
+}
+
+/// Turns a compiled document into the profile's format.
+fn typst_output(profile: &Profile, document: &PagedDocument) -> Result<Output> {
+    match profile.format {
+        Format::Svg => select_pages(profile, engine::svg(document)),
+        Format::Png => select_pages(profile, engine::png(document, profile.ppi)?),
+        _ => engine::pdf(document).map(Output::Document),
+    }
+}
+
+// Keeps the pages the profile asks for, numbered from 1.
+fn select_pages(profile: &Profile, pages: Vec<Vec<u8>>) -> Result<Output> {
+    let selected = profile
+        .pages
+        .select(pages.len())
+        .map_err(anyhow::Error::msg)?;
+    let mut pages: Vec<Option<Vec<u8>>> = pages.into_iter().map(Some).collect();
+    Ok(Output::Pages(
+        selected
+            .into_iter()
+            .filter_map(|page| Some((page, pages[page - 1].take()?)))
+            .collect(),
+    ))
+}
+
+/// Where an image of `page` goes when several pages are written: `out.png` becomes
+/// `out-2.png`.
+fn page_path(output: &Path, page: usize) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match output.extension() {
+        Some(ext) => format!("{}-{}.{}", stem, page, ext.to_string_lossy()),
+        None => format!("{}-{}", stem, page),
+    };
+    output.with_file_name(name)
+}
+
+fn save(profile: &Profile, output: &Output) -> Result<()> {
+    let files: Vec<(PathBuf, &[u8])> = match output {
+        Output::Document(document) => vec![(profile.output.clone(), document)],
+        Output::Pages(pages) if pages.len() == 1 => vec![(profile.output.clone(), &pages[0].1)],
+        Output::Pages(pages) => {
+            if is_stdio(&profile.output) {
+                anyhow::bail!(
+                    "Cannot write {} pages to stdout; pick one with --pages",
+                    pages.len()
+                );
+            }
+            pages
+                .iter()
+                .map(|(page, image)| (page_path(&profile.output, *page), image.as_slice()))
+                .collect()
+        }
+    };
+    for (path, contents) in &files {
+        write_output(path, contents).with_context(|| {
+            format!(
+                "Failed to write {} to {:?}",
+                profile.format,
+                display_path(path, "<stdout>")
+            )
+        })?;
+    }
+    match files.as_slice() {
+        [(path, _)] => status!(
+            path,
+            "Success! Resume exported to {:?}",
+            display_path(path, "<stdout>")
+        ),
+        _ => status!(
+            &profile.output,
+            "Success! Resume exported to {} page images alongside {:?}",
+            files.len(),
+            profile.output
+        ),
+    }
+    Ok(())
+}
+
+fn parse_param(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, found `{}`", arg)),
+    }
+}
+
+/// Reports a file that doesn't parse as a finding of its own, so machine-readable output still
+/// describes it; any other failure to read is passed on.
+fn report_syntax(err: anyhow::Error, output_format: OutputFormat) -> Result<()> {
+    match err.downcast::<ParseError>() {
+        Ok(err) => finish(
+            &err.path,
+            output_format,
+            &[err.diagnostic()],
+            Severity::Error,
+        ),
+        Err(err) => Err(err),
+    }
+}
+
+/// Prints the findings and fails if any of them is at least as severe as `threshold`.
+fn finish(
+    input: &Path,
+    output_format: OutputFormat,
+    diagnostics: &[Diagnostic],
+    threshold: Severity,
+) -> Result<()> {
+    let rendered = report::render(output_format, input, diagnostics);
+    match output_format {
+        OutputFormat::Human if !rendered.is_empty() => eprintln!("{}", rendered),
+        OutputFormat::Human => {}
+        _ => println!("{}", rendered),
+    }
+
+    let failures = diagnostics
+        .iter()
+        .filter(|d| d.severity >= threshold)
+        .count();
+    if failures > 0 {
+        anyhow::bail!("Validation failed: {} problem(s) in {:?}", failures, input);
+    }
+    if output_format == OutputFormat::Human && diagnostics.is_empty() {
+        println!("{:?}: Valid.", input);
+    }
     Ok(())
 }
@@ -0,0 +1,162 @@
+use std::fmt::Write;
+
+use crate::outline::{Entry, Outline};
+
+/// Renders CommonMark, for READMEs and the job portals that accept it.
+pub fn render(outline: &Outline) -> String {
+    let mut md = String::new();
+    if let Some(name) = &outline.name {
+        let _ = writeln!(md, "# {}\n", escape(name));
+    }
+    if let Some(label) = &outline.label {
+        let _ = writeln!(md, "**{}**\n", escape(label));
+    }
+    if !outline.contacts.is_empty() {
+        let contacts: Vec<String> = outline
+            .contacts
+            .iter()
+            .map(|contact| link(&contact.text, contact.href.as_deref()))
+            .collect();
+        let _ = writeln!(md, "{}\n", contacts.join(" · "));
+    }
+    if let Some(summary) = &outline.summary {
+        let _ = writeln!(md, "{}\n", escape(summary));
+    }
+    for section in &outline.sections {
+        let _ = writeln!(md, "## {}\n", section.title);
+        for entry in &section.entries {
+            self::entry(&mut md, entry);
+        }
+    }
+    // Exactly one newline at the end, however the last block finished.
+    md.truncate(md.trim_end().len());
+    md.push('\n');
+    md
+}
+
+fn entry(md: &mut String, entry: &Entry) {
+    let _ = writeln!(md, "### {}\n", link(&entry.title, entry.url.as_deref()));
+    let details: Vec<String> = [
+        entry
+            .subtitle
+            .as_deref()
+            .map(|s| format!("*{}*", escape(s))),
+        entry.dates.as_deref().map(escape),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !details.is_empty() {
+        let _ = writeln!(md, "{}\n", details.join(" · "));
+    }
+    if let Some(summary) = &entry.summary {
+        let _ = writeln!(md, "{}\n", escape(summary));
+    }
+    if !entry.bullets.is_empty() {
+        for bullet in &entry.bullets {
+            let _ = writeln!(md, "- {}", escape(bullet));
+        }
+        md.push('\n');
+    }
+    if !entry.keywords.is_empty() {
+        let keywords: Vec<String> = entry.keywords.iter().map(|k| escape(k)).collect();
+        let _ = writeln!(md, "**Keywords:** {}\n", keywords.join(", "));
+    }
+}
+
+fn link(text: &str, href: Option<&str>) -> String {
+    match href {
+        Some(href) => format!("[{}](<{}>)", escape(text), href.replace('>', "%3E")),
+        None => escape(text),
+    }
+}
+
+// Backslash-escapes whatever could otherwise start emphasis, a link, a heading or HTML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            escaped.push('\n');
+        }
+        let marker = list_marker(line);
+        for (j, c) in line.char_indices() {
+            if Some(j) == marker
+                || matches!(
+                    c,
+                    '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|'
+                )
+            {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+// Where a line would start a list item (`- `, `+ `, `1. `, `1) `), as the byte offset of
+// the character that makes it one.
+fn list_marker(line: &str) -> Option<usize> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    let rest = &line[indent..];
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let marker = match digits {
+        0 if rest.starts_with(['-', '+']) => indent,
+        1..=9 if rest[digits..].starts_with(['.', ')']) => indent + digits,
+        _ => return None,
+    };
+    let after = &line[marker + 1..];
+    (after.is_empty() || after.starts_with([' ', '\t'])).then_some(marker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outline::{Contact, Section};
+
+    #[test]
+    fn test_renders_headings_links_and_bullets() {
+        let outline = Outline {
+            name: Some("Ada Lovelace".to_string()),
+            contacts: vec![Contact {
+                kind: "Email".to_string(),
+                text: "ada@example.com".to_string(),
+                href: Some("mailto:ada@example.com".to_string()),
+            }],
+            sections: vec![Section {
+                title: "Work",
+                entries: vec![Entry {
+                    title: "Acme".to_string(),
+                    url: Some("https://acme.example/".to_string()),
+                    subtitle: Some("Engineer".to_string()),
+                    dates: Some("Jan 2020 — Present".to_string()),
+                    bullets: vec!["Rewrote *everything*".to_string()],
+                    keywords: vec!["Rust".to_string(), "C#".to_string()],
+                    ..Entry::default()
+                }],
+            }],
+            ..Outline::default()
+        };
+        assert_eq!(
+            render(&outline),
+            "# Ada Lovelace\n\n\
+             [ada@example.com](<mailto:ada@example.com>)\n\n\
+             ## Work\n\n\
+             ### [Acme](<https://acme.example/>)\n\n\
+             *Engineer* · Jan 2020 — Present\n\n\
+             - Rewrote \\*everything\\*\n\n\
+             **Keywords:** Rust, C\\#\n"
+        );
+    }
+
+    #[test]
+    fn test_escapes_list_markers_at_line_start() {
+        assert_eq!(escape("- 50% latency"), "\\- 50% latency");
+        assert_eq!(escape("+ more"), "\\+ more");
+        assert_eq!(escape("1. first\n2) second"), "1\\. first\n2\\) second");
+        assert_eq!(escape("> quoted"), "\\> quoted");
+        assert_eq!(escape("2024. A year"), "2024\\. A year");
+        // Not at the start of a line, or not followed by a space: left alone.
+        assert_eq!(escape("-50% cost, 3. place"), "-50% cost, 3. place");
+    }
+}
@@ -0,0 +1,263 @@
+use serde_json::{Map, Value};
+
+use crate::source_map::escape_token;
+
+/// The key of the removal marker. `{ "$remove": true }` in place of a value deletes that key;
+/// inside a keyed list, an item carrying `"$remove": true` deletes the item it matches.
+pub const REMOVE: &str = "$remove";
+
+// Lists whose items are matched up by a field instead of being replaced wholesale.
+fn item_key(path: &[String]) -> Option<&'static str> {
+    let path: Vec<&str> = path.iter().map(String::as_str).collect();
+    match path.as_slice() {
+        ["work"]
+        | ["projects"]
+        | ["certificates"]
+        | ["publications"]
+        | ["skills"]
+        | ["interests"]
+        | ["references"] => Some("name"),
+        ["volunteer"] => Some("organization"),
+        ["education"] => Some("institution"),
+        ["awards"] => Some("title"),
+        ["languages"] => Some("language"),
+        ["basics", "profiles"] => Some("network"),
+        _ => None,
+    }
+}
+
+fn is_marker(value: &Value) -> bool {
+    matches!(value, Value::Object(map) if map.len() == 1 && map.get(REMOVE) == Some(&Value::Bool(true)))
+}
+
+fn marked_for_removal(item: &Value) -> bool {
+    item.get(REMOVE) == Some(&Value::Bool(true))
+}
+
+/// Deep-merges `overlay` into `base`.
+///
+/// Objects merge key by key and scalars override. `null` means "no opinion", so sparse
+/// overlays and Dhall completions full of `None` leave the base alone. Sections such as
+/// `work` or `projects` merge items that share a key (`name`, `institution`, ...) and append
+/// the rest; every other list is replaced.
+pub fn merge(base: &mut Value, overlay: Value) {
+    merge_at(base, overlay, &mut Vec::new());
+}
+
+fn merge_at(base: &mut Value, overlay: Value, path: &mut Vec<String>) {
+    match (base, overlay, item_key(path)) {
+        (_, Value::Null, _) => {}
+        (Value::Object(base), Value::Object(overlay), _) => merge_objects(base, overlay, path),
+        (Value::Array(base), Value::Array(overlay), Some(key)) => {
+            merge_items(base, overlay, key, path)
+        }
+        (base, overlay, _) => *base = cleaned(overlay),
+    }
+}
+
+fn merge_objects(
+    base: &mut Map<String, Value>,
+    overlay: Map<String, Value>,
+    path: &mut Vec<String>,
+) {
+    for (key, value) in overlay {
+        if key == REMOVE || value.is_null() {
+            continue;
+        }
+        if is_marker(&value) {
+            base.shift_remove(&key);
+            continue;
+        }
+        match base.get_mut(&key) {
+            Some(existing) if !existing.is_null() => {
+                path.push(key);
+                merge_at(existing, value, path);
+                path.pop();
+            }
+            _ => {
+                base.insert(key, cleaned(value));
+            }
+        }
+    }
+}
+
+fn merge_items(base: &mut Vec<Value>, overlay: Vec<Value>, key: &str, path: &mut Vec<String>) {
+    for item in overlay {
+        let position = item.get(key).filter(|id| !id.is_null()).and_then(|id| {
+            base.iter()
+                .position(|existing| existing.get(key) == Some(id))
+        });
+        match position {
+            Some(index) if marked_for_removal(&item) => {
+                base.remove(index);
+            }
+            Some(index) => {
+                path.push(index.to_string());
+                merge_at(&mut base[index], item, path);
+                path.pop();
+            }
+            None if marked_for_removal(&item) => {}
+            None => base.push(cleaned(item)),
+        }
+    }
+}
+
+/// Finds where the value at `pointer` in `layer`, one of the documents merged into `merged`,
+/// ended up. Keyed list items are followed by their key, so positions survive the merge.
+pub fn follow(layer: &Value, merged: &Value, pointer: &str) -> Option<String> {
+    let mut path: Vec<String> = Vec::new();
+    let (mut from, mut to) = (layer, merged);
+    for token in pointer.split('/').skip(1) {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        let (next_from, next_to, step) = match (from, to) {
+            (Value::Object(f), Value::Object(t)) => (f.get(&token)?, t.get(&token)?, token),
+            (Value::Array(f), Value::Array(t)) => {
+                let index = token.parse::<usize>().ok()?;
+                let item = f.get(index)?;
+                let id = item_key(&path).and_then(|key| Some((key, item.get(key)?)));
+                let index = match id {
+                    Some((key, id)) if !id.is_null() => {
+                        t.iter().position(|other| other.get(key) == Some(id))?
+                    }
+                    _ => index,
+                };
+                (item, t.get(index)?, index.to_string())
+            }
+            _ => return None,
+        };
+        (from, to) = (next_from, next_to);
+        path.push(step);
+    }
+    Some(
+        path.iter()
+            .map(|token| format!("/{}", escape_token(token)))
+            .collect(),
+    )
+}
+
+// Markers only make sense against a base; anything left over once merged is dropped.
+fn cleaned(mut value: Value) -> Value {
+    match &mut value {
+        Value::Object(map) => {
+            map.retain(|key, v| key != REMOVE && !is_marker(v));
+            for v in map.values_mut() {
+                *v = cleaned(v.take());
+            }
+        }
+        Value::Array(items) => {
+            items.retain(|item| !marked_for_removal(item));
+            for item in items.iter_mut() {
+                *item = cleaned(item.take());
+            }
+        }
+        _ => {}
+    }
+    value
+}
+
+/// Replaces value markers with `null`, so an overlay can be checked against the schema
+/// without moving anything around.
+pub fn neutralize_markers(value: &mut Value) {
+    if is_marker(value) {
+        *value = Value::Null;
+        return;
+    }
+    match value {
+        Value::Object(map) => map.values_mut().for_each(neutralize_markers),
+        Value::Array(items) => items.iter_mut().for_each(neutralize_markers),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn merged(base: Value, overlays: &[Value]) -> Value {
+        let mut base = base;
+        for overlay in overlays {
+            merge(&mut base, overlay.clone());
+        }
+        base
+    }
+
+    #[test]
+    fn test_scalars_override_and_null_is_ignored() {
+        let result = merged(
+            json!({ "basics": { "name": "Ada", "label": "Engineer", "email": "a@b.co" } }),
+            &[json!({ "basics": { "label": "Staff Engineer", "email": null, "phone": "555" } })],
+        );
+        assert_eq!(
+            result,
+            json!({ "basics": { "name": "Ada", "label": "Staff Engineer", "email": "a@b.co", "phone": "555" } })
+        );
+    }
+
+    #[test]
+    fn test_sections_merge_by_key() {
+        let result = merged(
+            json!({ "work": [
+                { "name": "Acme", "position": "Dev", "highlights": ["a", "b"] },
+                { "name": "Initech", "position": "Intern" },
+            ] }),
+            &[json!({ "work": [
+                { "name": "Acme", "highlights": ["Tailored"] },
+                { "name": "Globex", "position": "Lead" },
+            ] })],
+        );
+        assert_eq!(
+            result,
+            json!({ "work": [
+                { "name": "Acme", "position": "Dev", "highlights": ["Tailored"] },
+                { "name": "Initech", "position": "Intern" },
+                { "name": "Globex", "position": "Lead" },
+            ] })
+        );
+    }
+
+    #[test]
+    fn test_removal_markers() {
+        let result = merged(
+            json!({
+                "basics": { "name": "Ada", "phone": "555" },
+                "projects": [{ "name": "Secret" }, { "name": "Public" }],
+            }),
+            &[
+                json!({ "basics": { "phone": { "$remove": true } } }),
+                json!({ "projects": [{ "name": "Secret", "$remove": true }, { "name": "Ghost", "$remove": true }] }),
+            ],
+        );
+        assert_eq!(
+            result,
+            json!({ "basics": { "name": "Ada" }, "projects": [{ "name": "Public" }] })
+        );
+    }
+
+    #[test]
+    fn test_neutralize_markers_keeps_positions() {
+        let mut overlay = json!({ "basics": { "phone": { "$remove": true } }, "work": [{ "name": "A", "$remove": true }] });
+        neutralize_markers(&mut overlay);
+        assert_eq!(
+            overlay,
+            json!({ "basics": { "phone": null }, "work": [{ "name": "A", "$remove": true }] })
+        );
+    }
+
+    #[test]
+    fn test_follow_tracks_items_across_the_merge() {
+        let base = json!({ "projects": [{ "name": "Secret" }, { "name": "Public", "highlights": ["a"] }] });
+        let overlay =
+            json!({ "projects": [{ "name": "Secret", "$remove": true }, { "name": "New" }] });
+        let result = merged(base.clone(), std::slice::from_ref(&overlay));
+        assert_eq!(
+            follow(&base, &result, "/projects/1/highlights/0").as_deref(),
+            Some("/projects/0/highlights/0")
+        );
+        assert_eq!(
+            follow(&overlay, &result, "/projects/1").as_deref(),
+            Some("/projects/1")
+        );
+        assert_eq!(follow(&overlay, &result, "/projects/0"), None);
+    }
+}
@@ -0,0 +1,271 @@
+use anyhow::Result;
+use std::fmt::Write;
+
+use crate::date::PartialDate;
+use crate::outline::{Entry, Outline};
+use crate::package::{self, escape};
+
+const MIMETYPE: &str = "application/vnd.oasis.opendocument.text";
+
+const NAMESPACES: &str = "xmlns:office=\"urn:oasis:names:tc:opendocument:xmlns:office:1.0\" \
+    xmlns:style=\"urn:oasis:names:tc:opendocument:xmlns:style:1.0\" \
+    xmlns:text=\"urn:oasis:names:tc:opendocument:xmlns:text:1.0\" \
+    xmlns:fo=\"urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0\" \
+    xmlns:xlink=\"http://www.w3.org/1999/xlink\" \
+    xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+    xmlns:meta=\"urn:oasis:names:tc:opendocument:xmlns:meta:1.0\"";
+
+const MANIFEST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.3">
+<manifest:file-entry manifest:full-path="/" manifest:version="1.3" manifest:media-type="application/vnd.oasis.opendocument.text"/>
+<manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/>
+<manifest:file-entry manifest:full-path="styles.xml" manifest:media-type="text/xml"/>
+<manifest:file-entry manifest:full-path="meta.xml" manifest:media-type="text/xml"/>
+</manifest:manifest>
+"#;
+
+// Style names follow LibreOffice's, so documents pick up the user's own styles when pasted.
+const STYLES: &str = r##"<office:styles>
+<style:default-style style:family="paragraph"><style:paragraph-properties fo:margin-top="0cm" fo:margin-bottom="0.14cm" fo:line-height="115%"/><style:text-properties fo:font-family="Carlito, Calibri, sans-serif" fo:font-size="11pt" {language}/></style:default-style>
+<style:style style:name="Standard" style:family="paragraph" style:class="text"/>
+<style:style style:name="Text_20_body" style:display-name="Text body" style:family="paragraph" style:parent-style-name="Standard" style:class="text"/>
+<style:style style:name="Title" style:family="paragraph" style:parent-style-name="Standard" style:next-style-name="Subtitle" style:class="chapter"><style:text-properties fo:font-family="Caladea, Cambria, serif" fo:font-size="24pt" fo:font-weight="bold"/></style:style>
+<style:style style:name="Subtitle" style:family="paragraph" style:parent-style-name="Standard" style:next-style-name="Text_20_body" style:class="chapter"><style:text-properties fo:color="#4c566a" fo:font-size="14pt"/></style:style>
+<style:style style:name="Heading" style:family="paragraph" style:parent-style-name="Standard" style:next-style-name="Text_20_body" style:class="text"><style:paragraph-properties fo:keep-with-next="always"/></style:style>
+<style:style style:name="Heading_20_1" style:display-name="Heading 1" style:family="paragraph" style:parent-style-name="Heading" style:next-style-name="Text_20_body" style:default-outline-level="1" style:class="text"><style:paragraph-properties fo:margin-top="0.56cm" fo:margin-bottom="0.21cm" fo:border-bottom="0.5pt solid #d8dee9" fo:padding-bottom="0.05cm"/><style:text-properties fo:font-family="Caladea, Cambria, serif" fo:color="#5e81ac" fo:font-size="15pt" fo:font-weight="bold"/></style:style>
+<style:style style:name="Heading_20_2" style:display-name="Heading 2" style:family="paragraph" style:parent-style-name="Heading" style:next-style-name="Text_20_body" style:default-outline-level="2" style:class="text"><style:paragraph-properties fo:margin-top="0.35cm" fo:margin-bottom="0.07cm"/><style:text-properties fo:font-size="12pt" fo:font-weight="bold"/></style:style>
+<style:style style:name="List_20_Bullet" style:display-name="List Bullet" style:family="paragraph" style:parent-style-name="Standard" style:class="list"><style:paragraph-properties fo:margin-bottom="0.07cm"/></style:style>
+<style:style style:name="Emphasis" style:family="text"><style:text-properties fo:font-style="italic"/></style:style>
+<style:style style:name="Strong_20_Emphasis" style:display-name="Strong Emphasis" style:family="text"><style:text-properties fo:font-weight="bold"/></style:style>
+<style:style style:name="Internet_20_link" style:display-name="Internet link" style:family="text"><style:text-properties fo:color="#5e81ac" style:text-underline-style="solid" style:text-underline-width="auto" style:text-underline-color="font-color"/></style:style>
+<text:list-style style:name="Bullets"><text:list-level-style-bullet text:level="1" text:bullet-char="•"><style:list-level-properties text:list-level-position-and-space-mode="label-alignment"><style:list-level-label-alignment text:label-followed-by="listtab" text:list-tab-stop-position="0.635cm" fo:text-indent="-0.635cm" fo:margin-left="0.635cm"/></style:list-level-properties></text:list-level-style-bullet></text:list-style>
+</office:styles>
+<office:automatic-styles>
+<style:page-layout style:name="Letter"><style:page-layout-properties fo:page-width="21.59cm" fo:page-height="27.94cm" fo:margin-top="1.9cm" fo:margin-bottom="1.9cm" fo:margin-left="1.9cm" fo:margin-right="1.9cm"/></style:page-layout>
+</office:automatic-styles>
+<office:master-styles><style:master-page style:name="Standard" style:page-layout-name="Letter"/></office:master-styles>
+"##;
+
+/// Renders an OpenDocument Text file from the same outline as the DOCX export.
+pub fn render(outline: &Outline, lang: &str, today: PartialDate) -> Result<Vec<u8>> {
+    let mut body = String::new();
+    if let Some(name) = &outline.name {
+        paragraph(&mut body, "Title", &escape(name));
+    }
+    if let Some(label) = &outline.label {
+        paragraph(&mut body, "Subtitle", &escape(label));
+    }
+    if !outline.contacts.is_empty() {
+        let contacts: Vec<String> = outline
+            .contacts
+            .iter()
+            .map(|contact| link(&contact.text, contact.href.as_deref()))
+            .collect();
+        paragraph(&mut body, "Text_20_body", &contacts.join(" · "));
+    }
+    if let Some(summary) = &outline.summary {
+        paragraph(&mut body, "Text_20_body", &lines(summary));
+    }
+    for section in &outline.sections {
+        heading(&mut body, 1, &escape(section.title));
+        for entry in &section.entries {
+            self::entry(&mut body, entry);
+        }
+    }
+
+    let content = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <office:document-content {NAMESPACES} office:version=\"1.3\">\n\
+         <office:body><office:text>\n{}</office:text></office:body>\n\
+         </office:document-content>\n",
+        body
+    );
+    let styles = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <office:document-styles {NAMESPACES} office:version=\"1.3\">\n{}\
+         </office:document-styles>\n",
+        STYLES.replace("{language}", &language(lang))
+    );
+    package::zip([
+        ("mimetype", MIMETYPE.to_string()),
+        ("META-INF/manifest.xml", MANIFEST.to_string()),
+        ("meta.xml", meta(outline, lang, today)),
+        ("styles.xml", styles),
+        ("content.xml", content),
+    ])
+}
+
+// `en-GB` becomes `fo:language="en" fo:country="GB"`.
+fn language(lang: &str) -> String {
+    let (language, country) = lang.split_once('-').unwrap_or((lang, ""));
+    let mut attributes = format!("fo:language=\"{}\"", escape(language));
+    if !country.is_empty() {
+        let _ = write!(attributes, " fo:country=\"{}\"", escape(country));
+    }
+    attributes
+}
+
+// The document properties LibreOffice shows under File > Properties.
+fn meta(outline: &Outline, lang: &str, today: PartialDate) -> String {
+    let mut meta = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <office:document-meta {NAMESPACES} office:version=\"1.3\">\n<office:meta>\n\
+         <meta:generator>resume-bakery</meta:generator>\n\
+         <dc:title>{}</dc:title>\n",
+        escape(&outline.title())
+    );
+    if let Some(name) = &outline.name {
+        let _ = writeln!(
+            meta,
+            "<meta:initial-creator>{0}</meta:initial-creator>\n<dc:creator>{0}</dc:creator>",
+            escape(name)
+        );
+    }
+    if let Some(label) = &outline.label {
+        let _ = writeln!(meta, "<dc:subject>{}</dc:subject>", escape(label));
+    }
+    for keyword in outline.keywords() {
+        let _ = writeln!(meta, "<meta:keyword>{}</meta:keyword>", escape(keyword));
+    }
+    let _ = writeln!(meta, "<dc:language>{}</dc:language>", escape(lang));
+    let today = today.first_day();
+    let _ = writeln!(
+        meta,
+        "<meta:creation-date>{0}T00:00:00</meta:creation-date>\n<dc:date>{0}T00:00:00</dc:date>",
+        today
+    );
+    meta.push_str("</office:meta>\n</office:document-meta>\n");
+    meta
+}
+
+fn paragraph(body: &mut String, style: &str, content: &str) {
+    let _ = writeln!(
+        body,
+        "<text:p text:style-name=\"{}\">{}</text:p>",
+        style, content
+    );
+}
+
+fn heading(body: &mut String, level: u8, content: &str) {
+    let _ = writeln!(
+        body,
+        "<text:h text:style-name=\"Heading_20_{0}\" text:outline-level=\"{0}\">{1}</text:h>",
+        level, content
+    );
+}
+
+fn entry(body: &mut String, entry: &Entry) {
+    heading(body, 2, &link(&entry.title, entry.url.as_deref()));
+
+    let details: Vec<String> = [
+        entry.subtitle.as_deref().map(|s| {
+            format!(
+                "<text:span text:style-name=\"Emphasis\">{}</text:span>",
+                escape(s)
+            )
+        }),
+        entry.dates.as_deref().map(escape),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !details.is_empty() {
+        paragraph(body, "Text_20_body", &details.join(" · "));
+    }
+    if let Some(summary) = &entry.summary {
+        paragraph(body, "Text_20_body", &lines(summary));
+    }
+    if !entry.bullets.is_empty() {
+        body.push_str("<text:list text:style-name=\"Bullets\">\n");
+        for bullet in &entry.bullets {
+            body.push_str("<text:list-item>");
+            let _ = write!(
+                body,
+                "<text:p text:style-name=\"List_20_Bullet\">{}</text:p>",
+                lines(bullet)
+            );
+            body.push_str("</text:list-item>\n");
+        }
+        body.push_str("</text:list>\n");
+    }
+    if !entry.keywords.is_empty() {
+        let keywords = format!(
+            "<text:span text:style-name=\"Strong_20_Emphasis\">Keywords:</text:span> {}",
+            escape(&entry.keywords.join(", "))
+        );
+        paragraph(body, "Text_20_body", &keywords);
+    }
+}
+
+fn link(text: &str, href: Option<&str>) -> String {
+    match href {
+        Some(href) => format!(
+            "<text:a xlink:type=\"simple\" xlink:href=\"{}\" \
+             text:style-name=\"Internet_20_link\">{}</text:a>",
+            escape(href),
+            escape(text)
+        ),
+        None => escape(text),
+    }
+}
+
+// ODF collapses newlines like any other whitespace, so they become explicit breaks.
+fn lines(text: &str) -> String {
+    text.split('\n')
+        .map(escape)
+        .collect::<Vec<_>>()
+        .join("<text:line-break/>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outline::Section;
+    use std::io::{Cursor, Read};
+
+    #[test]
+    fn test_writes_an_opendocument_package() {
+        let outline = Outline {
+            name: Some("Ada & Co".to_string()),
+            sections: vec![Section {
+                title: "Skills",
+                entries: vec![Entry {
+                    title: "Rust".to_string(),
+                    url: Some("https://rust-lang.org/".to_string()),
+                    bullets: vec!["Line one\nline two".to_string()],
+                    ..Entry::default()
+                }],
+            }],
+            ..Outline::default()
+        };
+        let odt = render(&outline, "en-GB", "2024-05-06".parse().unwrap()).unwrap();
+
+        // Readers recognise the format by a stored `mimetype` at a fixed offset.
+        assert_eq!(&odt[30..38], b"mimetype");
+        assert_eq!(&odt[38..38 + MIMETYPE.len()], MIMETYPE.as_bytes());
+
+        let mut archive = zip::ZipArchive::new(Cursor::new(odt)).unwrap();
+        let mut part = |name: &str| {
+            let mut contents = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut contents)
+                .unwrap();
+            contents
+        };
+        let content = part("content.xml");
+        assert!(content.contains("<text:p text:style-name=\"Title\">Ada &amp; Co</text:p>"));
+        assert!(content.contains(
+            "<text:h text:style-name=\"Heading_20_1\" text:outline-level=\"1\">Skills</text:h>"
+        ));
+        assert!(content.contains("xlink:href=\"https://rust-lang.org/\""));
+        assert!(content.contains("Line one<text:line-break/>line two"));
+
+        let meta = part("meta.xml");
+        assert!(meta.contains("<dc:title>Ada &amp; Co — Resume</dc:title>"));
+        assert!(meta.contains("<meta:keyword>Rust</meta:keyword>"));
+        assert!(meta.contains("<dc:date>2024-05-06T00:00:00</dc:date>"));
+        assert!(part("styles.xml").contains("fo:language=\"en\" fo:country=\"GB\""));
+    }
+}
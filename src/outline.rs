@@ -0,0 +1,420 @@
+use crate::date::{EndDate, PartialDate, Precision};
+use crate::json_resume::*;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// A resume reduced to what the exporters that bypass Typst need: a header, then titled
+/// sections of entries, in the order and wording the PDF template uses.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Outline {
+    pub name: Option<String>,
+    pub label: Option<String>,
+    pub summary: Option<String>,
+    pub contacts: Vec<Contact>,
+    pub sections: Vec<Section>,
+}
+
+/// One way of getting in touch, such as an email address or a profile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contact {
+    pub kind: String,
+    pub text: String,
+    pub href: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub title: &'static str,
+    pub entries: Vec<Entry>,
+}
+
+/// An item of a section. Which fields are set depends on the section, e.g. a skill has
+/// keywords but no dates.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Entry {
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub dates: Option<String>,
+    pub url: Option<String>,
+    pub summary: Option<String>,
+    pub bullets: Vec<String>,
+    pub keywords: Vec<String>,
+}
+
+impl Outline {
+    /// A title for document properties, e.g. "Ada Lovelace — Resume".
+    pub fn title(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{} — Resume", name),
+            None => "Resume".to_string(),
+        }
+    }
+
+    /// Keywords for document properties: the names of the skills.
+    pub fn keywords(&self) -> Vec<&str> {
+        self.sections
+            .iter()
+            .filter(|section| section.title == "Skills")
+            .flat_map(|section| &section.entries)
+            .map(|entry| entry.title.as_str())
+            .collect()
+    }
+}
+
+/// Formats a date like the PDF template: the year alone, or the month and year.
+pub fn format_date(date: &PartialDate) -> String {
+    match (date.precision(), date.month) {
+        (Precision::Year, _) | (_, None) => date.year.to_string(),
+        (_, Some(month)) => format!("{} {}", MONTHS[usize::from(month) - 1], date.year),
+    }
+}
+
+/// Formats a date range; ranges without a start are left out, as in the PDF template.
+pub fn format_range(start: Option<&PartialDate>, end: Option<&EndDate>) -> Option<String> {
+    let end = match end.and_then(EndDate::date) {
+        Some(end) => format_date(&end),
+        None => "Present".to_string(),
+    };
+    start.map(|start| format!("{} — {}", format_date(start), end))
+}
+
+impl From<&Resume> for Outline {
+    fn from(resume: &Resume) -> Self {
+        let mut outline = Outline::default();
+        if let Some(basics) = &resume.basics {
+            outline.name = basics.name.clone();
+            outline.label = basics.label.clone();
+            outline.summary = basics.summary.clone();
+            outline.contacts = contacts(basics);
+        }
+
+        let sections = [
+            section("Work", &resume.work, work),
+            section("Education", &resume.education, education),
+            section("Projects", &resume.projects, project),
+            section("Skills", &resume.skills, skill),
+            section("Certifications", &resume.certificates, certificate),
+            section("Awards", &resume.awards, award),
+            section("Publications", &resume.publications, publication),
+            section("Volunteer", &resume.volunteer, volunteer),
+            section("Languages", &resume.languages, language),
+            section("Interests", &resume.interests, interest),
+            section("References", &resume.references, reference),
+        ];
+        outline.sections = sections.into_iter().flatten().collect();
+        outline
+    }
+}
+
+fn section<T>(
+    title: &'static str,
+    items: &Option<Vec<T>>,
+    entry: impl Fn(&T) -> Entry,
+) -> Option<Section> {
+    let items = items.as_deref().filter(|items| !items.is_empty())?;
+    Some(Section {
+        title,
+        entries: items.iter().map(entry).collect(),
+    })
+}
+
+fn contacts(basics: &Basics) -> Vec<Contact> {
+    let mut contacts = Vec::new();
+    let mut add = |kind: &str, text: String, href: Option<String>| {
+        contacts.push(Contact {
+            kind: kind.to_string(),
+            text,
+            href,
+        })
+    };
+    if let Some(email) = &basics.email {
+        add("Email", email.clone(), Some(format!("mailto:{}", email)));
+    }
+    if let Some(phone) = &basics.phone {
+        let number: String = phone
+            .chars()
+            .filter(|c| c.is_ascii_digit() || *c == '+')
+            .collect();
+        add("Phone", phone.clone(), Some(format!("tel:{}", number)));
+    }
+    if let Some(url) = &basics.url {
+        add("Website", url.to_string(), Some(url.to_string()));
+    }
+    if let Some(location) = &basics.location {
+        let place = [&location.city, &location.region, &location.country_code]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !place.is_empty() {
+            add("Location", place, None);
+        }
+    }
+    for profile in basics.profiles.iter().flatten() {
+        let network = match &profile.network {
+            Some(NetworkType::Other(name)) => name.clone(),
+            Some(network) => format!("{:?}", network),
+            None => "Profile".to_string(),
+        };
+        let url = profile.url.as_ref().map(|url| url.to_string());
+        let Some(text) = profile.username.clone().or_else(|| url.clone()) else {
+            continue;
+        };
+        add(&network, text, url);
+    }
+    contacts
+}
+
+fn text(value: &Option<String>) -> String {
+    value.clone().unwrap_or_default()
+}
+
+fn link(url: &Option<Link>) -> Option<String> {
+    url.as_ref().map(|url| url.to_string())
+}
+
+fn highlights(highlights: &Option<Vec<Highlight>>) -> Vec<String> {
+    highlights
+        .iter()
+        .flatten()
+        .map(|h| h.text().to_string())
+        .collect()
+}
+
+// `NativeOrBilingual` reads as "Native Or Bilingual".
+fn words(variant: impl std::fmt::Debug) -> String {
+    let name = format!("{:?}", variant);
+    let mut spaced = String::new();
+    for (i, c) in name.char_indices() {
+        if i > 0 && c.is_uppercase() && name[..i].ends_with(|p: char| p.is_lowercase()) {
+            spaced.push(' ');
+        }
+        spaced.push(c);
+    }
+    spaced
+}
+
+/// How a degree reads in prose, e.g. "Bachelor's Degree".
+pub fn degree_name(degree: &DegreeType) -> String {
+    match degree {
+        DegreeType::HighSchoolDiploma => "High School Diploma".to_string(),
+        DegreeType::AssociateDegree => "Associate Degree".to_string(),
+        DegreeType::BachelorDegree => "Bachelor's Degree".to_string(),
+        DegreeType::MasterDegree => "Master's Degree".to_string(),
+        DegreeType::OnlineCourse => "Online Course".to_string(),
+        DegreeType::Ged => "GED".to_string(),
+        DegreeType::Mba => "MBA".to_string(),
+        DegreeType::Jd => "JD".to_string(),
+        DegreeType::Md => "MD".to_string(),
+        DegreeType::Other(name) => name.clone(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// How a language reads in prose, e.g. "Haitian Creole".
+pub fn language_name(language: &LanguageType) -> String {
+    match language {
+        LanguageType::Other(name) => name.clone(),
+        language => words(language),
+    }
+}
+
+fn score(score: &Score) -> String {
+    match score {
+        Score::GpaWeighted { score, scale } => format!("GPA {}/{}", score, scale),
+        Score::Percentage(percent) => format!("{}%", percent),
+        Score::PassFail(true) => "Pass".to_string(),
+        Score::PassFail(false) => "Fail".to_string(),
+        Score::LetterGrade(grade) => grade.clone(),
+        Score::Custom { score, scale } => format!("{}/{}", score, scale),
+        Score::Text(text) => text.clone(),
+    }
+}
+
+fn work(w: &Work) -> Entry {
+    Entry {
+        title: text(&w.name),
+        subtitle: w.position.clone(),
+        dates: format_range(w.start_date.as_ref(), w.end_date.as_ref()),
+        url: link(&w.url),
+        summary: w.summary.clone(),
+        bullets: highlights(&w.highlights),
+        ..Entry::default()
+    }
+}
+
+fn education(e: &Education) -> Entry {
+    let mut subtitle = match (e.study_type.as_ref().map(degree_name), &e.area) {
+        (Some(study_type), Some(area)) => Some(format!("{} in {}", study_type, area)),
+        (study_type, area) => study_type.or_else(|| area.clone()),
+    };
+    if let Some(s) = &e.score {
+        subtitle = Some(match subtitle {
+            Some(subtitle) => format!("{} ({})", subtitle, score(s)),
+            None => score(s),
+        });
+    }
+    Entry {
+        title: text(&e.institution),
+        subtitle,
+        dates: format_range(e.start_date.as_ref(), e.end_date.as_ref()),
+        url: link(&e.url),
+        bullets: e.courses.clone().unwrap_or_default(),
+        ..Entry::default()
+    }
+}
+
+fn project(p: &Project) -> Entry {
+    Entry {
+        title: text(&p.name),
+        subtitle: p.roles.as_ref().map(|roles| roles.join(", ")),
+        dates: format_range(p.start_date.as_ref(), p.end_date.as_ref()),
+        url: link(&p.url),
+        summary: p.description.clone(),
+        bullets: highlights(&p.highlights),
+        keywords: p.keywords.clone().unwrap_or_default(),
+    }
+}
+
+fn skill(s: &Skill) -> Entry {
+    Entry {
+        title: text(&s.name),
+        subtitle: s.level.as_ref().map(words),
+        keywords: s.keywords.clone().unwrap_or_default(),
+        ..Entry::default()
+    }
+}
+
+fn certificate(c: &Certificate) -> Entry {
+    Entry {
+        title: text(&c.name),
+        subtitle: c.issuer.clone(),
+        dates: c.date.as_ref().map(format_date),
+        url: link(&c.url),
+        ..Entry::default()
+    }
+}
+
+fn award(a: &Award) -> Entry {
+    Entry {
+        title: text(&a.title),
+        subtitle: a.awarder.clone(),
+        dates: a.date.as_ref().map(format_date),
+        summary: a.summary.clone(),
+        ..Entry::default()
+    }
+}
+
+fn publication(p: &Publication) -> Entry {
+    Entry {
+        title: text(&p.name),
+        subtitle: p.publisher.clone(),
+        dates: p.release_date.as_ref().map(format_date),
+        url: link(&p.url),
+        summary: p.summary.clone(),
+        ..Entry::default()
+    }
+}
+
+fn volunteer(v: &Volunteer) -> Entry {
+    Entry {
+        title: text(&v.organization),
+        subtitle: v.position.clone(),
+        dates: format_range(v.start_date.as_ref(), v.end_date.as_ref()),
+        url: link(&v.url),
+        summary: v.summary.clone(),
+        bullets: highlights(&v.highlights),
+        ..Entry::default()
+    }
+}
+
+fn language(l: &Language) -> Entry {
+    Entry {
+        title: l.language.as_ref().map(language_name).unwrap_or_default(),
+        subtitle: l.fluency.as_ref().map(|fluency| match fluency {
+            FluencyLevel::Other(text) => text.clone(),
+            fluency => words(fluency),
+        }),
+        ..Entry::default()
+    }
+}
+
+fn interest(i: &Interest) -> Entry {
+    Entry {
+        title: text(&i.name),
+        keywords: i.keywords.clone().unwrap_or_default(),
+        ..Entry::default()
+    }
+}
+
+fn reference(r: &Reference) -> Entry {
+    Entry {
+        title: text(&r.name),
+        summary: r.reference.clone(),
+        ..Entry::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dates_match_the_pdf_template() {
+        let date = |s: &str| s.parse::<PartialDate>().unwrap();
+        assert_eq!(format_date(&date("2020")), "2020");
+        assert_eq!(format_date(&date("2020-01")), "Jan 2020");
+        assert_eq!(format_date(&date("2020-12-24")), "Dec 2020");
+        assert_eq!(
+            format_range(Some(&date("2019-03")), None).as_deref(),
+            Some("Mar 2019 — Present")
+        );
+        assert_eq!(
+            format_range(Some(&date("2019")), Some(&"2021-06".parse().unwrap())).as_deref(),
+            Some("2019 — Jun 2021")
+        );
+        assert_eq!(format_range(None, Some(&EndDate::Present)), None);
+    }
+
+    #[test]
+    fn test_sections_follow_the_pdf_order() {
+        let resume: Resume = serde_json::from_value(serde_json::json!({
+            "basics": { "name": "Ada", "email": "ada@example.com" },
+            "languages": [{ "language": "English", "fluency": "NativeOrBilingual" }],
+            "skills": [{ "name": "Rust", "keywords": ["async"] }],
+            "education": [{
+                "institution": "MIT",
+                "area": "Computer Science",
+                "studyType": "BachelorDegree",
+                "score": { "Percentage": 91 }
+            }],
+            "work": [{ "name": "Acme", "startDate": "2020-01", "highlights": ["Shipped"] }],
+            "interests": [],
+        }))
+        .unwrap();
+        let outline = Outline::from(&resume);
+
+        let titles: Vec<_> = outline.sections.iter().map(|s| s.title).collect();
+        assert_eq!(titles, ["Work", "Education", "Skills", "Languages"]);
+        assert_eq!(
+            outline.contacts[0].href.as_deref(),
+            Some("mailto:ada@example.com")
+        );
+        assert_eq!(
+            outline.sections[0].entries[0].dates.as_deref(),
+            Some("Jan 2020 — Present")
+        );
+        assert_eq!(outline.sections[0].entries[0].bullets, ["Shipped"]);
+        assert_eq!(
+            outline.sections[1].entries[0].subtitle.as_deref(),
+            Some("Bachelor's Degree in Computer Science (91%)")
+        );
+        assert_eq!(
+            outline.sections[3].entries[0].subtitle.as_deref(),
+            Some("Native Or Bilingual")
+        );
+    }
+}
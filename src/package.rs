@@ -0,0 +1,31 @@
+use anyhow::Result;
+use std::io::{Cursor, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Zips the parts of an office document in the order given. A `mimetype` part is stored
+/// uncompressed, since ODF readers sniff it from the start of the file.
+pub fn zip<'a>(parts: impl IntoIterator<Item = (&'a str, String)>) -> Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, contents) in parts {
+        let method = if name == "mimetype" {
+            CompressionMethod::Stored
+        } else {
+            CompressionMethod::Deflated
+        };
+        zip.start_file(
+            name,
+            SimpleFileOptions::default().compression_method(method),
+        )?;
+        zip.write_all(contents.as_bytes())?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Escapes text for XML content and double-quoted attributes.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
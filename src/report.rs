@@ -0,0 +1,143 @@
+use clap::ValueEnum;
+use serde_json::{Value, json};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::validate::{Diagnostic, Severity};
+
+#[derive(ValueEnum, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Human,
+    Json,
+    Sarif,
+}
+
+/// Renders a set of findings for a single input file.
+pub fn render(format: OutputFormat, path: &Path, diagnostics: &[Diagnostic]) -> String {
+    match format {
+        OutputFormat::Human => diagnostics
+            .iter()
+            .map(|d| d.report(path))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        OutputFormat::Json => serde_json::to_string_pretty(&json!({
+            "file": path.display().to_string(),
+            "diagnostics": diagnostics.iter().map(to_json).collect::<Vec<_>>(),
+        }))
+        .unwrap(),
+        OutputFormat::Sarif => serde_json::to_string_pretty(&to_sarif(path, diagnostics)).unwrap(),
+    }
+}
+
+fn to_json(diagnostic: &Diagnostic) -> Value {
+    json!({
+        "ruleId": diagnostic.rule,
+        "severity": diagnostic.severity.to_string(),
+        "pointer": diagnostic.pointer,
+        "message": diagnostic.message,
+        "line": diagnostic.location.map(|l| l.line),
+        "column": diagnostic.location.map(|l| l.column),
+    })
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    }
+}
+
+// SARIF 2.1.0, the format GitHub code scanning and most review bots ingest.
+fn to_sarif(path: &Path, diagnostics: &[Diagnostic]) -> Value {
+    let uri = path.to_string_lossy().replace('\\', "/");
+    let rules: BTreeSet<&str> = diagnostics.iter().map(|d| d.rule).collect();
+
+    let results: Vec<Value> = diagnostics
+        .iter()
+        .map(|d| {
+            let mut physical = json!({ "artifactLocation": { "uri": uri } });
+            if let Some(location) = d.location {
+                physical["region"] = json!({
+                    "startLine": location.line,
+                    "startColumn": location.column,
+                });
+            }
+            json!({
+                "ruleId": d.rule,
+                "level": sarif_level(d.severity),
+                "message": { "text": d.message },
+                "locations": [{
+                    "physicalLocation": physical,
+                    "logicalLocations": [{ "fullyQualifiedName": d.pointer, "kind": "member" }],
+                }],
+                "properties": { "pointer": d.pointer },
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source_map::Location;
+
+    fn sample() -> Vec<Diagnostic> {
+        vec![Diagnostic {
+            rule: "invalid-date",
+            severity: Severity::Error,
+            pointer: "/work/0/endDate".to_string(),
+            message: "Invalid ISO 8601 date format".to_string(),
+            location: Some(Location {
+                line: 7,
+                column: 18,
+            }),
+        }]
+    }
+
+    #[test]
+    fn test_json_output() {
+        let out: Value =
+            serde_json::from_str(&render(OutputFormat::Json, Path::new("cv.json"), &sample()))
+                .unwrap();
+        assert_eq!(out["diagnostics"][0]["ruleId"], "invalid-date");
+        assert_eq!(out["diagnostics"][0]["pointer"], "/work/0/endDate");
+        assert_eq!(out["diagnostics"][0]["line"], 7);
+    }
+
+    #[test]
+    fn test_sarif_output() {
+        let out: Value = serde_json::from_str(&render(
+            OutputFormat::Sarif,
+            Path::new("cv.json"),
+            &sample(),
+        ))
+        .unwrap();
+        let result = &out["runs"][0]["results"][0];
+        assert_eq!(out["version"], "2.1.0");
+        assert_eq!(result["level"], "error");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["region"]["startLine"],
+            7
+        );
+        assert_eq!(
+            out["runs"][0]["tool"]["driver"]["rules"][0]["id"],
+            "invalid-date"
+        );
+    }
+}
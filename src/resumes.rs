@@ -1,12 +1,25 @@
+use std::collections::BTreeSet;
 use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
 
 use clap::ValueEnum;
 
-#[derive(ValueEnum, Clone, Debug)]
+#[derive(ValueEnum, Clone, Debug, Default)]
 pub enum Available {
+    #[default]
     Basic,
 }
 
+impl Available {
+    /// The template's entry file, relative to the template directory.
+    pub fn entry(&self) -> &'static str {
+        match self {
+            Self::Basic => "template.typ",
+        }
+    }
+}
+
 impl fmt::Display for Available {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -14,3 +27,128 @@ impl fmt::Display for Available {
         }
     }
 }
+
+#[derive(ValueEnum, Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Format {
+    /// A paginated document compiled from the Typst template
+    #[default]
+    Pdf,
+    /// A standalone web page with the stylesheet and fonts inlined
+    Html,
+    /// CommonMark, for READMEs and portals that accept it
+    Markdown,
+    /// Plain text wrapped for pasting into application forms
+    Text,
+    /// A Word document
+    Docx,
+    /// An OpenDocument text file
+    Odt,
+    /// One PNG image per page, rendered from the Typst template
+    Png,
+    /// One SVG image per page, rendered from the Typst template
+    Svg,
+    /// schema.org Person data as JSON-LD, for embedding in web pages
+    JsonLd,
+}
+
+impl Format {
+    /// The file extension documents of this format usually carry.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Html => "html",
+            Self::Markdown => "md",
+            Self::Text => "txt",
+            Self::Docx => "docx",
+            Self::Odt => "odt",
+            Self::Png => "png",
+            Self::Svg => "svg",
+            Self::JsonLd => "jsonld",
+        }
+    }
+
+    /// Whether documents of this format are compiled from a Typst template.
+    pub fn uses_typst(self) -> bool {
+        matches!(self, Self::Pdf | Self::Png | Self::Svg)
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_possible_value() {
+            Some(value) => write!(f, "{}", value.get_name()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Which pages to render, such as `1,3-4` or `2-`. Pages count from 1; no ranges at all
+/// means every page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageRanges(Vec<RangeInclusive<usize>>);
+
+impl PageRanges {
+    /// The selected page numbers of a document with `count` pages, in order.
+    pub fn select(&self, count: usize) -> Result<Vec<usize>, String> {
+        if self.0.is_empty() {
+            return Ok((1..=count).collect());
+        }
+        let mut pages = BTreeSet::new();
+        for range in &self.0 {
+            if *range.start() > count {
+                return Err(format!(
+                    "Page {} does not exist; the document has {} page(s)",
+                    range.start(),
+                    count
+                ));
+            }
+            pages.extend(*range.start()..=(*range.end()).min(count));
+        }
+        Ok(pages.into_iter().collect())
+    }
+}
+
+impl FromStr for PageRanges {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let page = |part: &str| match part.trim().parse::<usize>() {
+            Ok(page) if page > 0 => Ok(page),
+            _ => Err(format!("`{}` is not a page number", part.trim())),
+        };
+        let mut ranges = Vec::new();
+        for part in s.split(',').filter(|part| !part.trim().is_empty()) {
+            let range = match part.split_once('-') {
+                Some((start, end)) if end.trim().is_empty() => page(start)?..=usize::MAX,
+                Some((start, end)) => page(start)?..=page(end)?,
+                None => page(part)?..=page(part)?,
+            };
+            if range.is_empty() {
+                return Err(format!("`{}` ends before it starts", part.trim()));
+            }
+            ranges.push(range);
+        }
+        Ok(Self(ranges))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_ranges_select_pages_in_order() {
+        let ranges: PageRanges = "3-, 1".parse().unwrap();
+        assert_eq!(ranges.select(4), Ok(vec![1, 3, 4]));
+        assert_eq!(PageRanges::default().select(2), Ok(vec![1, 2]));
+        assert_eq!("2-3".parse::<PageRanges>().unwrap().select(2), Ok(vec![2]));
+        assert!("3".parse::<PageRanges>().unwrap().select(2).is_err());
+    }
+
+    #[test]
+    fn test_page_ranges_reject_nonsense() {
+        assert!("0".parse::<PageRanges>().is_err());
+        assert!("3-1".parse::<PageRanges>().is_err());
+        assert!("one".parse::<PageRanges>().is_err());
+    }
+}
@@ -0,0 +1,420 @@
+use serde_json::{Map, Value};
+
+use crate::json_resume::Resume;
+
+/// The JSON Schema (draft 2020-12) for resume documents, derived from the model.
+pub fn json() -> Value {
+    let mut schema = schemars::schema_for!(Resume).to_value();
+    schema["title"] = "Resume".into();
+    schema
+}
+
+/// Dhall types and `default` records for resume documents, generated from the JSON Schema
+/// so both always describe the same model.
+///
+/// Dhall has no untagged unions. A value the schema allows in several forms keeps only the
+/// first one, unless they are all the same Dhall type (an end date is `Text` either way). The
+/// generated file lists the definitions this narrows, such as tagged highlights, which have to
+/// be written in one of the other input formats.
+pub fn dhall() -> String {
+    let schema = json();
+    let mut generator = DhallGenerator {
+        defs: schema["$defs"].as_object().cloned().unwrap_or_default(),
+        visited: Vec::new(),
+        exports: Vec::new(),
+        narrowed: Vec::new(),
+        out: String::new(),
+    };
+    generator.define("Resume", &schema);
+
+    let mut header = String::from(
+        "-- Generated from the Rust model by `resume schema --dhall`; do not edit by hand.\n",
+    );
+    for (name, ty) in &generator.narrowed {
+        header += &format!(
+            "-- Dhall has no untagged unions, so {} can only be written as {} here.\n",
+            name, ty
+        );
+    }
+    format!(
+        "{}{}\nin  {{ {}\n    }}\n",
+        header,
+        generator.out,
+        generator.exports.join("\n    , ")
+    )
+}
+
+struct DhallGenerator {
+    defs: Map<String, Value>,
+    visited: Vec<String>,
+    exports: Vec<String>,
+    // Definitions whose other forms don't fit Dhall, with the type they are left with.
+    narrowed: Vec<(String, String)>,
+    out: String,
+}
+
+// What a definition turns into on the Dhall side.
+enum Shape {
+    // Plain strings such as dates and URLs are just `Text` in Dhall.
+    Inline,
+    Union,
+    Record,
+    // A record with optional fields, exposed as `{ Type, default }` for `::` completion.
+    // Only used when every required field has a zero value, so `::{=}` always works.
+    Schema,
+}
+
+fn shape(def: &Value) -> Shape {
+    if def.get("enum").is_some() || def.get("oneOf").is_some() || !free_text(def).is_empty() {
+        Shape::Union
+    } else if let Some(properties) = def["properties"].as_object() {
+        let required: Vec<&str> = def["required"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+        let defaultable = required.iter().all(|key| has_zero(&properties[*key]));
+        if required.len() < properties.len() && defaultable {
+            Shape::Schema
+        } else {
+            Shape::Record
+        }
+    } else {
+        Shape::Inline
+    }
+}
+
+// The untagged string forms of an enum that otherwise has named alternatives.
+fn free_text(def: &Value) -> Vec<&Value> {
+    let options = def["anyOf"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let tagged = |o: &Value| o.get("enum").is_some() || o["additionalProperties"] == false;
+    if !options.iter().any(tagged) {
+        return Vec::new();
+    }
+    options
+        .iter()
+        .filter(|o| !tagged(o) && o["type"] == "string")
+        .collect()
+}
+
+// Required naturals and lists can still be left out of a `::` completion, as 0 and [].
+fn has_zero(property: &Value) -> bool {
+    property["type"] == "array"
+        || (property["type"] == "integer" && property["minimum"].as_f64().is_some_and(|m| m >= 0.0))
+}
+
+fn label(name: &str) -> String {
+    const KEYWORDS: [&str; 16] = [
+        "if", "then", "else", "let", "in", "as", "using", "merge", "missing", "Infinity", "NaN",
+        "Some", "toMap", "assert", "forall", "with",
+    ];
+    let plain = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '/'));
+    if plain && !KEYWORDS.contains(&name) {
+        name.to_string()
+    } else {
+        format!("`{}`", name)
+    }
+}
+
+// Type applications need parentheses when used as an argument.
+fn argument(ty: String) -> String {
+    if ty.contains(' ') && !ty.starts_with('{') {
+        format!("({})", ty)
+    } else {
+        ty
+    }
+}
+
+impl DhallGenerator {
+    /// Emits a definition after everything it refers to, since Dhall `let`s can't look ahead.
+    fn define(&mut self, name: &str, def: &Value) {
+        if self.visited.iter().any(|v| v == name) {
+            return;
+        }
+        self.visited.push(name.to_string());
+
+        let body = match shape(def) {
+            Shape::Inline => return,
+            Shape::Union => {
+                if !free_text(def).is_empty() {
+                    self.narrowed.push((
+                        name.to_string(),
+                        "one of its listed alternatives".to_string(),
+                    ));
+                }
+                self.union(def)
+            }
+            Shape::Record => {
+                let fields = self.fields(def);
+                let lines: Vec<String> = fields
+                    .iter()
+                    .map(|(key, ty)| format!("{} : {}", label(key), ty))
+                    .collect();
+                format!("      {{ {}\n      }}", lines.join("\n      , "))
+            }
+            Shape::Schema => {
+                let fields = self.fields(def);
+                let types: Vec<String> = fields
+                    .iter()
+                    .map(|(key, ty)| format!("{} : {}", label(key), ty))
+                    .collect();
+                let defaults: Vec<String> = fields
+                    .iter()
+                    .map(|(key, ty)| {
+                        let value = match ty.strip_prefix("Optional ") {
+                            Some(inner) => format!("None {}", inner),
+                            None if ty.starts_with("List ") => format!("[] : {}", ty),
+                            None => "0".to_string(),
+                        };
+                        format!("{} = {}", label(key), value)
+                    })
+                    .collect();
+                format!(
+                    "      {{ Type =\n          {{ {}\n          }}\n      , default =\n          {{ {}\n          }}\n      }}",
+                    types.join("\n          , "),
+                    defaults.join("\n          , ")
+                )
+            }
+        };
+        self.out += &format!("\nlet {} =\n{}\n", name, body);
+        self.exports.push(name.to_string());
+    }
+
+    fn reference(&mut self, reference: &str) -> String {
+        let name = reference.trim_start_matches("#/$defs/");
+        let def = self.defs.get(name).cloned().unwrap_or_default();
+        self.define(name, &def);
+        match shape(&def) {
+            Shape::Inline => {
+                let ty = self.ty(&def);
+                if self.forms(&def).len() > 1 && !self.narrowed.iter().any(|(n, _)| n == name) {
+                    self.narrowed.push((name.to_string(), ty.clone()));
+                }
+                ty
+            }
+            Shape::Schema => format!("{}.Type", name),
+            Shape::Union | Shape::Record => name.to_string(),
+        }
+    }
+
+    fn fields(&mut self, def: &Value) -> Vec<(String, String)> {
+        let properties = def["properties"].as_object().cloned().unwrap_or_default();
+        properties
+            .iter()
+            .map(|(key, property)| (key.clone(), self.ty(property)))
+            .collect()
+    }
+
+    fn union(&mut self, def: &Value) -> String {
+        let mut alternatives = Vec::new();
+        let variants = match def["oneOf"].as_array().or_else(|| def["anyOf"].as_array()) {
+            Some(variants) => variants.clone(),
+            None => vec![def.clone()],
+        };
+        for variant in &variants {
+            if let Some(names) = variant["enum"].as_array() {
+                alternatives.extend(names.iter().filter_map(Value::as_str).map(label));
+            } else if let Some(name) = variant["const"].as_str() {
+                alternatives.push(label(name));
+            } else if let Some((name, payload)) = variant["properties"]
+                .as_object()
+                .and_then(|p| p.iter().next())
+            {
+                let payload = self.ty(payload);
+                alternatives.push(format!("{} : {}", label(name), payload));
+            }
+        }
+        format!("      < {}\n      >", alternatives.join("\n      | "))
+    }
+
+    /// The distinct Dhall types of the non-null alternatives of an `anyOf`.
+    fn forms(&mut self, schema: &Value) -> Vec<String> {
+        let mut forms: Vec<String> = Vec::new();
+        for option in schema["anyOf"].as_array().into_iter().flatten() {
+            if option["type"] != "null" {
+                let ty = self.ty(option);
+                if !forms.contains(&ty) {
+                    forms.push(ty);
+                }
+            }
+        }
+        forms
+    }
+
+    fn ty(&mut self, schema: &Value) -> String {
+        if let Some(reference) = schema["$ref"].as_str() {
+            return self.reference(reference);
+        }
+        if let Some(options) = schema["anyOf"].as_array() {
+            let nullable = options.iter().any(|o| o["type"] == "null");
+            // The first form is the simplest one; see `dhall` for what is lost.
+            let inner = self
+                .forms(schema)
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| "Text".to_string());
+            return if nullable {
+                format!("Optional {}", argument(inner))
+            } else {
+                inner
+            };
+        }
+
+        let (base, nullable) = match &schema["type"] {
+            Value::Array(types) => (
+                types
+                    .iter()
+                    .find(|t| *t != "null")
+                    .cloned()
+                    .unwrap_or_default(),
+                types.iter().any(|t| t == "null"),
+            ),
+            other => (other.clone(), false),
+        };
+        let ty = match base.as_str().unwrap_or_default() {
+            "boolean" => "Bool".to_string(),
+            "number" => "Double".to_string(),
+            "integer" if schema["minimum"].as_f64().is_some_and(|m| m >= 0.0) => {
+                "Natural".to_string()
+            }
+            "integer" => "Integer".to_string(),
+            "array" => format!("List {}", argument(self.ty(&schema["items"]))),
+            "object" => {
+                let fields: Vec<String> = self
+                    .fields(schema)
+                    .iter()
+                    .map(|(key, ty)| format!("{} : {}", label(key), ty))
+                    .collect();
+                format!("{{ {} }}", fields.join(", "))
+            }
+            _ => "Text".to_string(),
+        };
+        if nullable {
+            format!("Optional {}", argument(ty))
+        } else {
+            ty
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition<'a>(schema: &'a Value, name: &str) -> &'a Value {
+        &schema["$defs"][name]
+    }
+
+    #[test]
+    fn test_describes_renamed_fields_and_constraints() {
+        let schema = json();
+        assert_eq!(
+            schema["$schema"],
+            "https://json-schema.org/draft/2020-12/schema"
+        );
+        assert!(schema["properties"]["$schema"].is_object());
+
+        let work = definition(&schema, "Work");
+        assert!(work["properties"]["startDate"].is_object());
+        assert!(work["properties"]["employmentType"].is_object());
+        assert_eq!(
+            definition(&schema, "Basics")["properties"]["email"]["format"],
+            "email"
+        );
+        assert_eq!(
+            definition(&schema, "Address")["properties"]["countryCode"]["pattern"],
+            "^[A-Z]{2}$"
+        );
+        assert!(definition(&schema, "Project")["properties"]["type"].is_object());
+    }
+
+    #[test]
+    fn test_enums_are_externally_tagged() {
+        let schema = json();
+        let location = serde_json::to_string(definition(&schema, "LocationType")).unwrap();
+        assert!(location.contains("\"Remote\""));
+        assert!(location.contains("\"OnSite\""));
+        assert!(location.contains("\"onSite\""));
+
+        let score = serde_json::to_string(definition(&schema, "Score")).unwrap();
+        assert!(score.contains("\"GPA_Weighted\""));
+        assert!(score.contains("\"LetterGrade\""));
+    }
+
+    #[test]
+    fn test_date_pattern_matches_the_parser() {
+        let schema = json();
+        let pattern = definition(&schema, "PartialDate")["pattern"]
+            .as_str()
+            .unwrap();
+        let pattern = regex::Regex::new(pattern).unwrap();
+        for valid in ["2023", "2023-04", "2023-04-01"] {
+            assert!(pattern.is_match(valid), "{}", valid);
+        }
+        for invalid in ["23", "2023-13", "2023-4", "2023-04-32", "April 2023"] {
+            assert!(!pattern.is_match(invalid), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_checked_in_dhall_schema_is_up_to_date() {
+        assert!(
+            include_str!("../resume-schema.dhall") == dhall(),
+            "resume-schema.dhall is stale; regenerate it with `resume schema --dhall -o resume-schema.dhall`"
+        );
+    }
+
+    #[test]
+    fn test_dhall_narrows_only_what_it_cannot_express() {
+        let dhall = dhall();
+        assert!(dhall.contains("so Highlight can only be written as Text here"));
+        assert!(!dhall.contains("so EndDate"));
+
+        // An ongoing entry is still a plain end date...
+        let ongoing = format!(
+            "let R = {} in R.Work::{{ endDate = Some \"present\", highlights = Some [ \"a\" ] }}",
+            dhall
+        );
+        let parsed = serde_dhall::from_str(&ongoing).parse::<serde_dhall::SimpleValue>();
+        assert!(parsed.is_ok(), "{:?}", parsed.err());
+
+        // ...but a tagged highlight has no Dhall type to take.
+        let tagged = format!(
+            "let R = {} in R.Work::{{ highlights = Some [ {{ text = \"a\", tags = [ \"x\" ] }} ] }}",
+            dhall
+        );
+        assert!(
+            serde_dhall::from_str(&tagged)
+                .parse::<serde_dhall::SimpleValue>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_dhall_defaults_cover_required_fields() {
+        let dhall = dhall();
+        for expression in [
+            "R.SemVer::{=}",
+            "R.SemVer::{ major = 1 }",
+            "{ primary = None R.PronounSet, additional = [] : List R.PronounSet, display = \"they/them\" } : R.Pronouns",
+        ] {
+            let source = format!("let R = {} in {}", dhall, expression);
+            let parsed = serde_dhall::from_str(&source).parse::<serde_dhall::SimpleValue>();
+            assert!(parsed.is_ok(), "{}: {:?}", expression, parsed.err());
+        }
+    }
+
+    #[test]
+    fn test_dhall_schema_type_checks() {
+        let defaults = serde_dhall::from_str(&format!("({}).Resume.default", dhall()))
+            .parse::<serde_dhall::SimpleValue>();
+        assert!(defaults.is_ok(), "{:?}", defaults.err());
+    }
+}
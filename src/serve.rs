@@ -0,0 +1,298 @@
+use anyhow::{Context, Result};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+use typst::layout::PagedDocument;
+
+use crate::engine;
+use crate::package::escape;
+
+// Browsers drop quiet event streams eventually, and a failed write is how we notice they left.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// The latest rendering: one SVG per page plus the PDF, or what went wrong.
+#[derive(Default)]
+struct Snapshot {
+    version: u64,
+    pages: Vec<String>,
+    pdf: Vec<u8>,
+    error: Option<String>,
+}
+
+#[derive(Default)]
+struct Shared {
+    snapshot: Mutex<Snapshot>,
+    published: Condvar,
+}
+
+/// A small HTTP server for previewing a resume in a browser. Open pages learn about new
+/// renderings through server-sent events and reload themselves.
+pub struct PreviewServer {
+    listener: TcpListener,
+    // A second handle on the same socket for the accepting thread, until it starts.
+    acceptor: Option<TcpListener>,
+    shared: Arc<Shared>,
+}
+
+impl PreviewServer {
+    pub fn bind(address: &str) -> Result<Self> {
+        let listener = TcpListener::bind(address)
+            .with_context(|| format!("Could not listen on {}", address))?;
+        let acceptor = listener
+            .try_clone()
+            .with_context(|| format!("Could not listen on {}", address))?;
+        Ok(Self {
+            listener,
+            acceptor: Some(acceptor),
+            shared: Arc::default(),
+        })
+    }
+
+    pub fn address(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accepts connections in the background, one thread each. Only the first call starts
+    /// accepting; the server keeps listening on one socket.
+    pub fn spawn(&mut self) {
+        let Some(listener) = self.acceptor.take() else {
+            return;
+        };
+        let shared = Arc::clone(&self.shared);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || {
+                    let reader = match stream.try_clone() {
+                        Ok(reader) => reader,
+                        Err(_) => return,
+                    };
+                    // A client hanging up half way is not worth reporting.
+                    let _ = respond(&shared, BufReader::new(reader), stream);
+                });
+            }
+        });
+    }
+
+    /// Replaces what the preview shows and tells open pages to reload.
+    pub fn publish(&self, rendered: Result<PagedDocument>) {
+        let mut snapshot = self.shared.snapshot.lock().unwrap();
+        match rendered.and_then(|document| Ok((document.pages.clone(), engine::pdf(&document)?))) {
+            Ok((pages, pdf)) => {
+                snapshot.pages = pages.iter().map(typst_svg::svg).collect();
+                snapshot.pdf = pdf;
+                snapshot.error = None;
+            }
+            // Keep showing the last good pages underneath the error.
+            Err(e) => snapshot.error = Some(format!("{:#}", e)),
+        }
+        snapshot.version += 1;
+        self.shared.published.notify_all();
+    }
+}
+
+fn respond(shared: &Shared, mut reader: impl BufRead, mut writer: impl Write) -> io::Result<()> {
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // The headers carry nothing we need, but have to be read before answering.
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    if method != "GET" {
+        return send(
+            &mut writer,
+            "405 Method Not Allowed",
+            "text/plain",
+            b"Only GET is supported",
+        );
+    }
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    match path {
+        "/" => {
+            let page = index(&shared.snapshot.lock().unwrap());
+            send(
+                &mut writer,
+                "200 OK",
+                "text/html; charset=utf-8",
+                page.as_bytes(),
+            )
+        }
+        "/resume.pdf" => {
+            let pdf = shared.snapshot.lock().unwrap().pdf.clone();
+            send(&mut writer, "200 OK", "application/pdf", &pdf)
+        }
+        "/events" => {
+            let since = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("since="))
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0);
+            events(shared, since, writer)
+        }
+        _ => {
+            let svg = path
+                .strip_prefix("/pages/")
+                .and_then(|page| page.strip_suffix(".svg"))
+                .and_then(|page| page.parse::<usize>().ok())
+                .and_then(|page| {
+                    let snapshot = shared.snapshot.lock().unwrap();
+                    snapshot.pages.get(page.checked_sub(1)?).cloned()
+                });
+            match svg {
+                Some(svg) => send(&mut writer, "200 OK", "image/svg+xml", svg.as_bytes()),
+                None => send(&mut writer, "404 Not Found", "text/plain", b"Not found"),
+            }
+        }
+    }
+}
+
+fn send(writer: &mut impl Write, status: &str, content_type: &str, body: &[u8]) -> io::Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    writer.write_all(body)?;
+    writer.flush()
+}
+
+// Streams the version number of every rendering newer than `since`, until the client leaves.
+fn events(shared: &Shared, mut since: u64, mut writer: impl Write) -> io::Result<()> {
+    writer.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-store\r\n\r\n",
+    )?;
+    writer.flush()?;
+    loop {
+        let snapshot = shared.snapshot.lock().unwrap();
+        let (snapshot, _) = shared
+            .published
+            .wait_timeout_while(snapshot, KEEP_ALIVE, |s| s.version <= since)
+            .unwrap();
+        let version = snapshot.version;
+        drop(snapshot);
+
+        if version > since {
+            since = version;
+            write!(writer, "data: {}\n\n", version)?;
+        } else {
+            writer.write_all(b": keep-alive\n\n")?;
+        }
+        writer.flush()?;
+    }
+}
+
+fn index(snapshot: &Snapshot) -> String {
+    let error = snapshot
+        .error
+        .as_deref()
+        .map(|e| format!("<pre class=\"error\">{}</pre>", escape(e)))
+        .unwrap_or_default();
+    let pages: String = (1..=snapshot.pages.len())
+        .map(|page| {
+            format!(
+                "<img src=\"/pages/{}.svg?v={}\" alt=\"Page {}\">",
+                page, snapshot.version, page
+            )
+        })
+        .collect();
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Resume preview</title>
+<style>
+  body {{ margin: 0; background: #d8dee9; font-family: sans-serif; }}
+  nav {{ padding: 0.5em 1em; text-align: right; }}
+  img {{ display: block; margin: 1em auto; background: white; box-shadow: 0 2px 8px #0003; }}
+  .error {{ margin: 1em; padding: 1em; background: #bf616a; color: white; white-space: pre-wrap; }}
+</style>
+</head>
+<body>
+<nav><a href="/resume.pdf">Download PDF</a></nav>
+{}{}
+<script>
+  new EventSource("/events?since={}").onmessage = () => location.reload();
+</script>
+</body>
+</html>
+"#,
+        error, pages, snapshot.version
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(shared: &Shared, target: &str) -> String {
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target);
+        let mut response = Vec::new();
+        respond(shared, request.as_bytes(), &mut response).unwrap();
+        String::from_utf8(response).unwrap()
+    }
+
+    fn shared() -> Shared {
+        let shared = Shared::default();
+        *shared.snapshot.lock().unwrap() = Snapshot {
+            version: 3,
+            pages: vec!["<svg>one</svg>".to_string(), "<svg>two</svg>".to_string()],
+            pdf: b"%PDF".to_vec(),
+            error: Some("unclosed <delimiter>".to_string()),
+        };
+        shared
+    }
+
+    #[test]
+    fn test_index_lists_pages_and_errors() {
+        let page = get(&shared(), "/");
+        assert!(page.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(page.contains("/pages/2.svg?v=3"));
+        assert!(page.contains("unclosed &lt;delimiter&gt;"));
+        assert!(page.contains("/events?since=3"));
+    }
+
+    #[test]
+    fn test_serves_pages_by_number() {
+        let shared = shared();
+        assert!(get(&shared, "/pages/2.svg?v=3").ends_with("<svg>two</svg>"));
+        assert!(get(&shared, "/pages/0.svg").starts_with("HTTP/1.1 404"));
+        assert!(get(&shared, "/pages/3.svg").starts_with("HTTP/1.1 404"));
+        assert!(get(&shared, "/resume.pdf").ends_with("%PDF"));
+    }
+
+    #[test]
+    fn test_events_report_newer_versions() {
+        struct Hangup(Vec<u8>);
+        impl Write for Hangup {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            // Let the headers and the first event through, then act like a closed tab.
+            fn flush(&mut self) -> io::Result<()> {
+                if self.0.windows(6).any(|w| w == b"data: ") {
+                    return Err(io::ErrorKind::BrokenPipe.into());
+                }
+                Ok(())
+            }
+        }
+
+        let mut stream = Hangup(Vec::new());
+        assert!(events(&shared(), 2, &mut stream).is_err());
+        assert!(
+            String::from_utf8(stream.0)
+                .unwrap()
+                .ends_with("data: 3\n\n")
+        );
+    }
+}
@@ -0,0 +1,517 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Escapes a single reference token as described in RFC 6901.
+pub fn escape_token(token: &str) -> Cow<'_, str> {
+    if token.contains(['~', '/']) {
+        Cow::Owned(token.replace('~', "~0").replace('/', "~1"))
+    } else {
+        Cow::Borrowed(token)
+    }
+}
+
+/// Maps JSON pointers to the position where the value they reference starts.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    positions: HashMap<String, Location>,
+    // For documents merged from several files: which overlay each position is in, counting
+    // from 1. Positions not listed are in the base file.
+    origins: HashMap<String, usize>,
+}
+
+impl SourceMap {
+    /// Indexes a JSON document. Scanning stops quietly at the first syntax error,
+    /// leaving whatever was indexed up to that point.
+    pub fn json(source: &str) -> Self {
+        let mut scanner = Scanner::new(source);
+        scanner.value(String::new());
+        Self {
+            positions: scanner.positions,
+            origins: HashMap::new(),
+        }
+    }
+
+    /// Indexes the record and list literals of a Dhall document. Dhall is normalised before
+    /// it is checked, so this follows the literal the file evaluates to, after its last
+    /// top-level `in`; values bound by `let` or imported keep the position they're used at.
+    pub fn dhall(source: &str) -> Self {
+        let mut scanner = Scanner::new(source);
+        scanner.skip_bindings();
+        scanner.term(String::new());
+        Self {
+            positions: scanner.positions,
+            origins: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, pointer: String, location: Location) {
+        self.positions.insert(pointer, location);
+    }
+
+    /// Records a position in the given overlay, or in the base file for `0`.
+    pub fn insert_from(&mut self, origin: usize, pointer: String, location: Location) {
+        if origin == 0 {
+            self.origins.remove(&pointer);
+        } else {
+            self.origins.insert(pointer.clone(), origin);
+        }
+        self.positions.insert(pointer, location);
+    }
+
+    /// Every indexed pointer, with the file it is in and its position there.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, usize, Location)> {
+        self.positions.iter().map(|(pointer, location)| {
+            let origin = self.origins.get(pointer).copied().unwrap_or(0);
+            (pointer.as_str(), origin, *location)
+        })
+    }
+
+    /// Finds the closest indexed position, walking up the pointer until one is known.
+    pub fn locate(&self, pointer: &str) -> Option<Location> {
+        self.locate_from(pointer).map(|(_, location)| location)
+    }
+
+    /// Like `locate`, along with the file the position is in (`0` for the base file).
+    pub fn locate_from(&self, pointer: &str) -> Option<(usize, Location)> {
+        let mut pointer = pointer;
+        loop {
+            if let Some(location) = self.positions.get(pointer) {
+                let origin = self.origins.get(pointer).copied().unwrap_or(0);
+                return Some((origin, *location));
+            }
+            pointer = &pointer[..pointer.rfind('/')?];
+        }
+    }
+}
+
+struct Scanner {
+    chars: Vec<char>,
+    index: usize,
+    location: Location,
+    positions: HashMap<String, Location>,
+}
+
+impl Scanner {
+    fn new(source: &str) -> Self {
+        Self {
+            chars: source.chars().collect(),
+            index: 0,
+            location: Location { line: 1, column: 1 },
+            positions: HashMap::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.index + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.index += 1;
+        if c == '\n' {
+            self.location.line += 1;
+            self.location.column = 1;
+        } else {
+            self.location.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn eat(&mut self, expected: char) -> Option<()> {
+        self.skip_whitespace();
+        (self.bump()? == expected).then_some(())
+    }
+
+    fn value(&mut self, pointer: String) -> Option<()> {
+        self.skip_whitespace();
+        self.positions.insert(pointer.clone(), self.location);
+        match self.peek()? {
+            '{' => self.object(pointer),
+            '[' => self.array(pointer),
+            '"' => self.string().map(drop),
+            _ => {
+                while self
+                    .peek()
+                    .is_some_and(|c| !matches!(c, ',' | ']' | '}') && !c.is_whitespace())
+                {
+                    self.bump();
+                }
+                Some(())
+            }
+        }
+    }
+
+    fn object(&mut self, pointer: String) -> Option<()> {
+        self.bump();
+        self.skip_whitespace();
+        if self.peek()? == '}' {
+            self.bump();
+            return Some(());
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.eat(':')?;
+            self.value(format!("{}/{}", pointer, escape_token(&key)))?;
+            self.skip_whitespace();
+            match self.bump()? {
+                ',' => continue,
+                '}' => return Some(()),
+                _ => return None,
+            }
+        }
+    }
+
+    fn array(&mut self, pointer: String) -> Option<()> {
+        self.bump();
+        self.skip_whitespace();
+        if self.peek()? == ']' {
+            self.bump();
+            return Some(());
+        }
+        for index in 0.. {
+            self.value(format!("{}/{}", pointer, index))?;
+            self.skip_whitespace();
+            match self.bump()? {
+                ',' => continue,
+                ']' => break,
+                _ => return None,
+            }
+        }
+        Some(())
+    }
+
+    fn string(&mut self) -> Option<String> {
+        if self.bump()? != '"' {
+            return None;
+        }
+        let mut out = String::new();
+        loop {
+            match self.bump()? {
+                '"' => return Some(out),
+                '\\' => match self.bump()? {
+                    'n' => out.push('\n'),
+                    't' => out.push('\t'),
+                    'r' => out.push('\r'),
+                    'b' => out.push('\u{8}'),
+                    'f' => out.push('\u{c}'),
+                    'u' => {
+                        let hex: String = (0..4).filter_map(|_| self.bump()).collect();
+                        let c = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                        out.push(c.unwrap_or(char::REPLACEMENT_CHARACTER));
+                    }
+                    other => out.push(other),
+                },
+                c => out.push(c),
+            }
+        }
+    }
+}
+
+// Dhall, or as much of it as it takes to find the literals a resume is written in.
+impl Scanner {
+    fn skip_trivia(&mut self) {
+        loop {
+            self.skip_whitespace();
+            match (self.peek(), self.peek_at(1)) {
+                (Some('-'), Some('-')) => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.bump();
+                    }
+                }
+                (Some('{'), Some('-')) => {
+                    let mut depth = 0;
+                    while let Some(c) = self.bump() {
+                        match (c, self.peek()) {
+                            ('{', Some('-')) => depth += 1,
+                            ('-', Some('}')) => {
+                                self.bump();
+                                depth -= 1;
+                                if depth == 0 {
+                                    break;
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn word(&mut self) -> String {
+        let mut word = String::new();
+        if self.peek() == Some('`') {
+            self.bump();
+            while let Some(c) = self.bump().filter(|c| *c != '`') {
+                word.push(c);
+            }
+            return word;
+        }
+        while let Some(c) = self
+            .peek()
+            .filter(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '/'))
+        {
+            word.push(c);
+            self.bump();
+        }
+        word
+    }
+
+    // Moves past the `let` bindings to the expression after the last top-level `in`.
+    fn skip_bindings(&mut self) {
+        let mut body = (self.index, self.location);
+        let mut depth = 0usize;
+        loop {
+            self.skip_trivia();
+            match self.peek() {
+                None => break,
+                Some('{' | '[' | '(') => {
+                    depth += 1;
+                    self.bump();
+                }
+                Some('}' | ']' | ')') => {
+                    depth = depth.saturating_sub(1);
+                    self.bump();
+                }
+                Some('"' | '\'') => self.skip_text(),
+                Some(c) if c.is_alphabetic() || c == '_' => {
+                    if self.word() == "in" && depth == 0 {
+                        body = (self.index, self.location);
+                    }
+                }
+                Some(_) => {
+                    self.bump();
+                }
+            }
+        }
+        (self.index, self.location) = body;
+    }
+
+    fn skip_text(&mut self) {
+        if self.peek() == Some('"') {
+            self.bump();
+            while let Some(c) = self.bump() {
+                match c {
+                    '"' => return,
+                    '\\' => {
+                        self.bump();
+                    }
+                    _ => {}
+                }
+            }
+        } else if self.peek_at(1) == Some('\'') {
+            self.bump();
+            self.bump();
+            while let Some(c) = self.bump() {
+                if c == '\'' && self.peek() == Some('\'') {
+                    self.bump();
+                    // `'''` escapes two quotes; anything else closes the text.
+                    if self.peek() != Some('\'') {
+                        return;
+                    }
+                    self.bump();
+                }
+            }
+        } else {
+            self.bump();
+        }
+    }
+
+    // Skips the rest of an expression: everything up to a separator at the same depth.
+    fn skip_expression(&mut self) {
+        let mut depth = 0usize;
+        loop {
+            self.skip_trivia();
+            match self.peek() {
+                None => return,
+                Some(',' | '}' | ']' | ')') if depth == 0 => return,
+                Some('{' | '[' | '(') => {
+                    depth += 1;
+                    self.bump();
+                }
+                Some('}' | ']' | ')') => {
+                    depth -= 1;
+                    self.bump();
+                }
+                Some('"' | '\'') => self.skip_text(),
+                Some(_) => {
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    fn term(&mut self, pointer: String) -> Option<()> {
+        self.skip_trivia();
+        self.positions.insert(pointer.clone(), self.location);
+        match self.peek()? {
+            '{' => return self.record(pointer),
+            '[' => return self.list(pointer),
+            c if c.is_alphabetic() || c == '_' => {
+                // `Some x` and completions such as `Basics::{ … }` wrap the literal itself.
+                let mut name = self.word();
+                while self.peek() == Some('.') && self.peek_at(1).is_some_and(char::is_alphabetic) {
+                    self.bump();
+                    name = self.word();
+                }
+                if name == "Some" {
+                    return self.term(pointer);
+                }
+                self.skip_trivia();
+                if self.peek() == Some(':') && self.peek_at(1) == Some(':') {
+                    self.bump();
+                    self.bump();
+                    self.skip_trivia();
+                    if self.peek() == Some('{') {
+                        return self.record(pointer);
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.skip_expression();
+        Some(())
+    }
+
+    fn record(&mut self, pointer: String) -> Option<()> {
+        self.bump();
+        loop {
+            self.skip_trivia();
+            match self.peek()? {
+                '}' => {
+                    self.bump();
+                    return Some(());
+                }
+                ',' | '=' => {
+                    self.bump();
+                }
+                ']' | ')' => return None,
+                _ => {
+                    // Dotted labels such as `meta.canonical = …` nest records.
+                    let mut field = pointer.clone();
+                    loop {
+                        let label = self.word();
+                        if label.is_empty() {
+                            break;
+                        }
+                        field = format!("{}/{}", field, escape_token(&label));
+                        self.skip_trivia();
+                        if self.peek() != Some('.') {
+                            break;
+                        }
+                        self.bump();
+                        self.skip_trivia();
+                    }
+                    if self.peek() == Some('=') {
+                        self.bump();
+                        self.term(field)?;
+                    }
+                    self.skip_expression();
+                }
+            }
+        }
+    }
+
+    fn list(&mut self, pointer: String) -> Option<()> {
+        self.bump();
+        let mut index = 0;
+        loop {
+            self.skip_trivia();
+            match self.peek()? {
+                ']' => {
+                    self.bump();
+                    return Some(());
+                }
+                ',' => {
+                    self.bump();
+                }
+                '}' | ')' => return None,
+                _ => {
+                    self.term(format!("{}/{}", pointer, index))?;
+                    self.skip_expression();
+                    index += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locates_nested_values() {
+        let source = "{\n  \"work\": [\n    { \"startDate\": \"2023-19\" }\n  ]\n}";
+        let map = SourceMap::json(source);
+        assert_eq!(
+            map.locate("/work/0/startDate"),
+            Some(Location {
+                line: 3,
+                column: 20
+            })
+        );
+        assert_eq!(
+            map.locate("/work"),
+            Some(Location {
+                line: 2,
+                column: 11
+            })
+        );
+    }
+
+    #[test]
+    fn test_falls_back_to_parent() {
+        let map = SourceMap::json(r#"{ "basics": {} }"#);
+        assert_eq!(
+            map.locate("/basics/email"),
+            Some(Location {
+                line: 1,
+                column: 13
+            })
+        );
+    }
+
+    #[test]
+    fn test_escapes_tokens() {
+        let map = SourceMap::json(r#"{ "a/b": 1, "c~d": 2 }"#);
+        assert!(map.locate(&format!("/{}", escape_token("a/b"))).is_some());
+        assert_eq!(escape_token("c~d"), "c~0d");
+    }
+
+    #[test]
+    fn test_locates_dhall_literals() {
+        let source = "-- a resume\nlet Work = ./schema.dhall\nlet x = { a = 1 } in x\n\nin  Resume::{\n    , basics = Some Basics::{ name = Some \"Ada\" }\n    , work =\n      [ Work::{ , startDate = Some \"2020\" }\n      , { {- inline -} endDate = \"present\" }\n      ] : List Work\n    , meta.canonical = \"https://example.com\"\n    }\n";
+        let map = SourceMap::dhall(source);
+        let at = |pointer| map.locate(pointer).map(|l| l.to_string());
+        assert_eq!(at("/basics/name").as_deref(), Some("6:43"));
+        assert_eq!(at("/work/0/startDate").as_deref(), Some("8:36"));
+        assert_eq!(at("/work/1/endDate").as_deref(), Some("9:34"));
+        assert_eq!(at("/meta/canonical").as_deref(), Some("11:24"));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::json_resume::*;
+
+/// Which tagged entries and highlights make it into an export.
+///
+/// Untagged items belong to every cut. A tagged item is dropped when it carries an excluded
+/// tag, or when tags are included and it carries none of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl TagFilter {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    fn keeps(&self, tags: &[String]) -> bool {
+        if tags.is_empty() {
+            return true;
+        }
+        if tags.iter().any(|tag| self.exclude.contains(tag)) {
+            return false;
+        }
+        self.include.is_empty() || tags.iter().any(|tag| self.include.contains(tag))
+    }
+
+    /// Drops the entries and highlights this filter rules out.
+    pub fn apply(&self, resume: &mut Resume) {
+        if self.is_empty() {
+            return;
+        }
+        self.retain(&mut resume.work);
+        self.retain(&mut resume.volunteer);
+        self.retain(&mut resume.education);
+        self.retain(&mut resume.awards);
+        self.retain(&mut resume.certificates);
+        self.retain(&mut resume.publications);
+        self.retain(&mut resume.skills);
+        self.retain(&mut resume.languages);
+        self.retain(&mut resume.interests);
+        self.retain(&mut resume.references);
+        self.retain(&mut resume.projects);
+
+        let highlights = resume
+            .work
+            .iter_mut()
+            .flatten()
+            .map(|w| &mut w.highlights)
+            .chain(
+                resume
+                    .volunteer
+                    .iter_mut()
+                    .flatten()
+                    .map(|v| &mut v.highlights),
+            )
+            .chain(
+                resume
+                    .projects
+                    .iter_mut()
+                    .flatten()
+                    .map(|p| &mut p.highlights),
+            );
+        for highlights in highlights.flatten() {
+            highlights.retain(|h| self.keeps(h.tags()));
+        }
+    }
+
+    fn retain<T: Tagged>(&self, items: &mut Option<Vec<T>>) {
+        if let Some(items) = items {
+            items.retain(|item| self.keeps(item.tags()));
+        }
+    }
+}
+
+trait Tagged {
+    fn tags(&self) -> &[String];
+}
+
+macro_rules! tagged {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Tagged for $ty {
+                fn tags(&self) -> &[String] {
+                    self.tags.as_deref().unwrap_or_default()
+                }
+            }
+        )*
+    };
+}
+
+tagged!(
+    Work,
+    Volunteer,
+    Education,
+    Award,
+    Certificate,
+    Publication,
+    Skill,
+    Language,
+    Interest,
+    Reference,
+    Project,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filtered(filter: &TagFilter) -> serde_json::Value {
+        let mut resume: Resume = serde_json::from_value(serde_json::json!({
+            "work": [
+                { "name": "Everywhere" },
+                { "name": "Backend", "tags": ["backend"], "highlights": [
+                    "Plain",
+                    { "text": "Led a team", "tags": ["management"] },
+                    { "text": "Sharded Postgres", "tags": ["backend"] },
+                ] },
+                { "name": "Lab", "tags": ["academic", "research"] },
+            ],
+            "skills": [{ "name": "Rust", "tags": ["backend"] }, { "name": "Writing" }],
+        }))
+        .unwrap();
+        filter.apply(&mut resume);
+        serde_json::to_value(&resume).unwrap()
+    }
+
+    fn names(value: &serde_json::Value, section: &str) -> Vec<String> {
+        value[section]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_include_keeps_untagged_and_matching() {
+        let value = filtered(&TagFilter {
+            include: vec!["backend".to_string()],
+            ..TagFilter::default()
+        });
+        assert_eq!(names(&value, "work"), ["Everywhere", "Backend"]);
+        assert_eq!(names(&value, "skills"), ["Rust", "Writing"]);
+        assert_eq!(
+            value["work"][1]["highlights"],
+            serde_json::json!(["Plain", { "text": "Sharded Postgres", "tags": ["backend"] }])
+        );
+    }
+
+    #[test]
+    fn test_exclude_wins_over_include() {
+        let value = filtered(&TagFilter {
+            include: vec!["research".to_string()],
+            exclude: vec!["academic".to_string()],
+        });
+        assert_eq!(names(&value, "work"), ["Everywhere"]);
+        assert_eq!(names(&value, "skills"), ["Writing"]);
+    }
+}
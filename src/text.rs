@@ -0,0 +1,168 @@
+use crate::outline::{Entry, Outline};
+
+/// Renders plain text that survives being pasted into a form field. Paragraphs are wrapped
+/// at `width` columns, or left as they are when `width` is zero.
+pub fn render(outline: &Outline, width: usize) -> String {
+    let mut text = String::new();
+    if let Some(name) = &outline.name {
+        text.push_str(&name.to_uppercase());
+        text.push('\n');
+    }
+    if let Some(label) = &outline.label {
+        text.push_str(&wrap(label, width, "", ""));
+    }
+    if !outline.contacts.is_empty() {
+        text.push('\n');
+        for contact in &outline.contacts {
+            let line = match &contact.href {
+                // Spell out web links whose text hides them; mailto: and tel: add nothing.
+                Some(href) if href.starts_with("http") && *href != contact.text => {
+                    format!("{}: {} ({})", contact.kind, contact.text, href)
+                }
+                _ => format!("{}: {}", contact.kind, contact.text),
+            };
+            text.push_str(&line);
+            text.push('\n');
+        }
+    }
+    if let Some(summary) = &outline.summary {
+        text.push('\n');
+        text.push_str(&wrap(summary, width, "", ""));
+    }
+    for section in &outline.sections {
+        text.push('\n');
+        text.push_str(&section.title.to_uppercase());
+        text.push('\n');
+        text.push_str(&"=".repeat(section.title.chars().count()));
+        text.push('\n');
+        for entry in &section.entries {
+            text.push('\n');
+            self::entry(&mut text, entry, width);
+        }
+    }
+    if text.is_empty() {
+        return text;
+    }
+    text.truncate(text.trim_end().len());
+    text.push('\n');
+    text
+}
+
+fn entry(text: &mut String, entry: &Entry, width: usize) {
+    text.push_str(&wrap(&entry.title, width, "", ""));
+    let details: Vec<&str> = [entry.subtitle.as_deref(), entry.dates.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect();
+    if !details.is_empty() {
+        text.push_str(&wrap(&details.join(" | "), width, "", ""));
+    }
+    if let Some(url) = &entry.url {
+        text.push_str(url);
+        text.push('\n');
+    }
+    if let Some(summary) = &entry.summary {
+        text.push_str(&wrap(summary, width, "", ""));
+    }
+    for bullet in &entry.bullets {
+        text.push_str(&wrap(bullet, width, "- ", "  "));
+    }
+    if !entry.keywords.is_empty() {
+        let keywords = format!("Keywords: {}", entry.keywords.join(", "));
+        text.push_str(&wrap(&keywords, width, "", "  "));
+    }
+}
+
+/// Fills each line of `paragraph` greedily, starting it with `first` and continuing with
+/// `rest`. Words longer than a line are kept whole rather than split.
+fn wrap(paragraph: &str, width: usize, first: &str, rest: &str) -> String {
+    let mut wrapped = String::new();
+    let mut prefix = first;
+    for line in paragraph.lines() {
+        let mut column = 0;
+        for word in line.split_whitespace() {
+            let len = word.chars().count();
+            if column > prefix.chars().count() && width > 0 && column + 1 + len > width {
+                wrapped.push('\n');
+                prefix = rest;
+                column = 0;
+            }
+            if column == 0 {
+                wrapped.push_str(prefix);
+                column = prefix.chars().count();
+            } else {
+                wrapped.push(' ');
+                column += 1;
+            }
+            wrapped.push_str(word);
+            column += len;
+        }
+        if column == 0 {
+            // Keep blank lines between paragraphs.
+            wrapped.push_str(prefix.trim_end());
+        }
+        wrapped.push('\n');
+        prefix = rest;
+    }
+    wrapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outline::Section;
+
+    #[test]
+    fn test_wrap_fills_lines_with_hanging_indent() {
+        assert_eq!(
+            wrap("one two three four five", 9, "- ", "  "),
+            "- one two\n  three\n  four\n  five\n"
+        );
+        assert_eq!(wrap("unbreakable", 4, "", ""), "unbreakable\n");
+        assert_eq!(wrap("first\n\nsecond", 0, "", ""), "first\n\nsecond\n");
+        assert_eq!(
+            wrap("no wrapping at all", 0, "", ""),
+            "no wrapping at all\n"
+        );
+    }
+
+    #[test]
+    fn test_renders_sections_in_order() {
+        let outline = Outline {
+            name: Some("Ada Lovelace".to_string()),
+            sections: vec![
+                Section {
+                    title: "Work",
+                    entries: vec![Entry {
+                        title: "Acme".to_string(),
+                        subtitle: Some("Engineer".to_string()),
+                        dates: Some("Jan 2020 — Present".to_string()),
+                        bullets: vec!["Rewrote the billing system in Rust".to_string()],
+                        ..Entry::default()
+                    }],
+                },
+                Section {
+                    title: "Skills",
+                    entries: vec![Entry {
+                        title: "Rust".to_string(),
+                        keywords: vec!["async".to_string(), "embedded".to_string()],
+                        ..Entry::default()
+                    }],
+                },
+            ],
+            ..Outline::default()
+        };
+        assert_eq!(
+            render(&outline, 24),
+            "ADA LOVELACE\n\n\
+             WORK\n====\n\n\
+             Acme\n\
+             Engineer | Jan 2020 —\n\
+             Present\n\
+             - Rewrote the billing\n  system in Rust\n\n\
+             SKILLS\n======\n\n\
+             Rust\n\
+             Keywords: async,\n  embedded\n"
+        );
+    }
+}
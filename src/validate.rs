@@ -0,0 +1,654 @@
+#[cfg(feature = "schema")]
+use regex::Regex;
+#[cfg(feature = "schema")]
+use serde::Deserialize;
+#[cfg(feature = "schema")]
+use serde_json::Map;
+use serde_json::Value;
+#[cfg(feature = "schema")]
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+#[cfg(feature = "schema")]
+use std::sync::LazyLock;
+
+use crate::input::Document;
+use crate::json_resume::*;
+#[cfg(feature = "schema")]
+use crate::schema;
+use crate::source_map::Location;
+#[cfg(feature = "schema")]
+use crate::source_map::escape_token;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A single finding, addressed by a JSON pointer into the document.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub pointer: String,
+    pub message: String,
+    pub location: Option<Location>,
+}
+
+impl Diagnostic {
+    /// Renders the diagnostic the way compilers do: `file:line:column: severity[rule]: pointer: message`.
+    pub fn report(&self, path: &Path) -> String {
+        match self.location {
+            Some(_) => format!("{}:{}", path.display(), self),
+            None => format!("{}: {}", path.display(), self),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "(root)"
+        } else {
+            &self.pointer
+        };
+        if let Some(location) = self.location {
+            write!(f, "{}: ", location)?;
+        }
+        write!(
+            f,
+            "{}[{}]: {}: {}",
+            self.severity, self.rule, pointer, self.message
+        )
+    }
+}
+
+/// Checks the whole document and returns every problem found, in document order.
+/// In strict mode, keys the model doesn't know about are reported too.
+pub fn validate(document: &Document, strict: bool) -> Vec<Diagnostic> {
+    locate(document, check(&document.value, strict, false))
+}
+
+/// Checks one layer of a merged document. Layers may leave out fields that another one
+/// provides, so only what is there is checked; the merged result is validated as a whole.
+pub fn validate_layer(document: &Document, strict: bool) -> Vec<Diagnostic> {
+    locate(document, check(&document.value, strict, true))
+}
+
+/// Fills in where each finding is in the document and puts them in file order.
+pub fn locate(document: &Document, mut diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+    for diagnostic in &mut diagnostics {
+        diagnostic.location = document.locate(&diagnostic.pointer);
+    }
+    // Findings without a position go last, in the order they were found.
+    diagnostics.sort_by_key(|d| (d.location.is_none(), d.location));
+    diagnostics
+}
+
+// The schema is derived from the model, so walking it keeps these checks in step with serde.
+#[cfg(feature = "schema")]
+static SCHEMA: LazyLock<Value> = LazyLock::new(schema::json);
+
+/// Checks an untyped value against the resume schema, leaving locations unset.
+/// A `partial` value may omit required fields.
+#[cfg(feature = "schema")]
+fn check(value: &Value, strict: bool, partial: bool) -> Vec<Diagnostic> {
+    let mut validator = Validator::new(&SCHEMA, strict, partial);
+    validator.check(&SCHEMA, value);
+
+    // Fall back to serde itself in case the schema is looser than the model somewhere.
+    if validator.diagnostics.is_empty()
+        && !partial
+        && let Err(e) = serde_json::from_value::<Resume>(value.clone())
+    {
+        validator.error("schema", e);
+    }
+    validator.diagnostics
+}
+
+/// Without the schema only serde can check the value. It stops at the first problem, takes
+/// unknown keys for custom fields, and can't check a layer on its own.
+#[cfg(not(feature = "schema"))]
+fn check(value: &Value, _strict: bool, partial: bool) -> Vec<Diagnostic> {
+    match serde_json::from_value::<Resume>(value.clone()) {
+        Err(e) if !partial => vec![Diagnostic {
+            rule: "schema",
+            severity: Severity::Error,
+            pointer: String::new(),
+            message: e.to_string(),
+            location: None,
+        }],
+        _ => Vec::new(),
+    }
+}
+
+/// Walks an untyped document alongside the JSON Schema, collecting every violation
+/// instead of stopping at the first one like serde does.
+#[cfg(feature = "schema")]
+struct Validator<'a> {
+    defs: &'a Map<String, Value>,
+    strict: bool,
+    partial: bool,
+    pointer: Vec<String>,
+    diagnostics: Vec<Diagnostic>,
+    patterns: HashMap<&'a str, Option<Regex>>,
+}
+
+#[cfg(feature = "schema")]
+static NO_DEFS: LazyLock<Map<String, Value>> = LazyLock::new(Map::new);
+
+#[cfg(feature = "schema")]
+impl<'a> Validator<'a> {
+    fn new(schema: &'a Value, strict: bool, partial: bool) -> Self {
+        Self {
+            defs: schema["$defs"].as_object().unwrap_or(&NO_DEFS),
+            strict,
+            partial,
+            pointer: Vec::new(),
+            diagnostics: Vec::new(),
+            patterns: HashMap::new(),
+        }
+    }
+
+    fn error(&mut self, rule: &'static str, message: impl fmt::Display) {
+        let pointer: String = self
+            .pointer
+            .iter()
+            .map(|token| format!("/{}", escape_token(token)))
+            .collect();
+        self.diagnostics.push(Diagnostic {
+            rule,
+            severity: Severity::Error,
+            pointer,
+            message: message.to_string(),
+            location: None,
+        });
+    }
+
+    fn at(&mut self, token: impl ToString, check: impl FnOnce(&mut Self)) {
+        self.pointer.push(token.to_string());
+        check(self);
+        self.pointer.pop();
+    }
+
+    /// Follows a `$ref` into `$defs`.
+    fn resolve(&self, schema: &'a Value) -> &'a Value {
+        match schema["$ref"].as_str() {
+            Some(reference) => {
+                let name = reference.trim_start_matches("#/$defs/");
+                self.defs.get(name).map_or(schema, |def| self.resolve(def))
+            }
+            None => schema,
+        }
+    }
+
+    fn check(&mut self, schema: &'a Value, value: &Value) {
+        if let Some(reference) = schema["$ref"].as_str()
+            && let Some(parse) = parser(reference.trim_start_matches("#/$defs/"))
+        {
+            if let Err(e) = parse(value) {
+                self.error("invalid-date", e);
+            }
+            return;
+        }
+        let schema = self.resolve(schema);
+        if let Some(options) = alternatives(schema) {
+            return self.alternative(options, value);
+        }
+        if !self.fits(schema, value) {
+            let message = format!("expected {}, found {}", expected(schema), kind(value));
+            return self.error("invalid-type", message);
+        }
+        if let Some(variants) = schema["enum"].as_array()
+            && !variants.contains(value)
+        {
+            let names: Vec<&str> = variants.iter().filter_map(Value::as_str).collect();
+            return self.error("unknown-variant", unknown_variant(value, &names));
+        }
+        match value {
+            Value::Object(map) => self.object(schema, map),
+            Value::Array(items) => {
+                if let Some(item) = schema.get("items") {
+                    for (index, value) in items.iter().enumerate() {
+                        self.at(index, |v| v.check(item, value));
+                    }
+                }
+            }
+            Value::String(text) => self.string(schema, text, value),
+            Value::Number(number) => {
+                if let Some(minimum) = schema["minimum"].as_f64()
+                    && number.as_f64().is_some_and(|n| n < minimum)
+                {
+                    self.error(
+                        "invalid-value",
+                        format!("expected at least {}, found {}", minimum, number),
+                    );
+                }
+            }
+            Value::Null | Value::Bool(_) => {}
+        }
+    }
+
+    fn object(&mut self, schema: &'a Value, map: &Map<String, Value>) {
+        let Some(properties) = schema["properties"].as_object() else {
+            return;
+        };
+        for (key, value) in map {
+            match properties.get(key) {
+                Some(property) => self.at(key, |v| v.check(property, value)),
+                None if self.strict => self.unknown_field(key, properties),
+                None => {}
+            }
+        }
+        if self.partial {
+            return;
+        }
+        let required = schema["required"].as_array().into_iter().flatten();
+        for key in required.filter_map(Value::as_str) {
+            if !map.contains_key(key) {
+                self.error("missing-field", format!("missing field `{}`", key));
+            }
+        }
+    }
+
+    // These keys would otherwise vanish into `additional_properties` without a trace.
+    // `x-` keys are the conventional way to extend a resume and `$` keys are directives
+    // such as overlay removal markers, so both are left alone.
+    fn unknown_field(&mut self, key: &str, properties: &Map<String, Value>) {
+        if key.starts_with("x-") || key.starts_with('$') {
+            return;
+        }
+        let known: Vec<String> = properties.keys().cloned().collect();
+        let message = match suggest(key, &known) {
+            Some(candidate) => format!("unknown field `{}`, did you mean `{}`?", key, candidate),
+            None => format!("unknown field `{}`", key),
+        };
+        self.at(key, |v| v.error("unknown-field", message));
+    }
+
+    fn string(&mut self, schema: &'a Value, text: &str, value: &Value) {
+        match schema["format"].as_str() {
+            Some("email") => {
+                if let Err(e) = deserialize_email(value) {
+                    self.error("invalid-email", e);
+                }
+            }
+            Some("uri") => {
+                if let Err(e) = url::Url::parse(text) {
+                    self.error("invalid-value", format!("invalid URL `{}`: {}", text, e));
+                }
+            }
+            _ => {
+                let Some(pattern) = schema["pattern"].as_str() else {
+                    return;
+                };
+                let compiled = self
+                    .patterns
+                    .entry(pattern)
+                    .or_insert_with(|| Regex::new(pattern).ok());
+                if compiled.as_ref().is_some_and(|re| !re.is_match(text)) {
+                    let message = format!("`{}` does not match `{}`", text, pattern);
+                    self.error("invalid-value", message);
+                }
+            }
+        }
+    }
+
+    // A value with several possible forms: optional values, bullets that may carry tags,
+    // and enums, which serde tags externally. Only the forms of the right JSON type are
+    // considered; when a single one remains, its problems are reported in detail.
+    fn alternative(&mut self, options: &'a [Value], value: &Value) {
+        let candidates: Vec<&'a Value> = options.iter().filter(|o| self.fits(o, value)).collect();
+        if candidates.is_empty() {
+            let forms: Vec<String> = options.iter().map(|o| expected(self.resolve(o))).collect();
+            let message = format!("expected {}, found {}", forms.join(" or "), kind(value));
+            return self.error("invalid-type", message);
+        }
+        let variants: Vec<&'a Value> = candidates
+            .iter()
+            .copied()
+            .filter(|o| self.is_variant(o, value))
+            .collect();
+        match (candidates.as_slice(), variants.as_slice()) {
+            ([only], _) | (_, [only]) => self.check(only, value),
+            _ if candidates.iter().any(|o| self.passes(o, value)) => {}
+            _ => {
+                let names: Vec<&str> = options.iter().flat_map(|o| self.names(o)).collect();
+                if names.is_empty() {
+                    self.check(candidates[0], value);
+                } else {
+                    self.error("unknown-variant", unknown_variant(value, &names));
+                }
+            }
+        }
+    }
+
+    /// Whether the value has a JSON type the schema allows, looking through references
+    /// and alternatives.
+    fn fits(&self, schema: &'a Value, value: &Value) -> bool {
+        let schema = self.resolve(schema);
+        if let Some(options) = alternatives(schema) {
+            return options.iter().any(|o| self.fits(o, value));
+        }
+        match &schema["type"] {
+            Value::String(ty) => has_type(ty, value),
+            Value::Array(types) => types
+                .iter()
+                .filter_map(Value::as_str)
+                .any(|ty| has_type(ty, value)),
+            _ => true,
+        }
+    }
+
+    // Whether the value names this variant of an externally tagged enum.
+    fn is_variant(&self, schema: &'a Value, value: &Value) -> bool {
+        let schema = self.resolve(schema);
+        if let Some(variants) = schema["enum"].as_array() {
+            return variants.contains(value);
+        }
+        match (value, schema["additionalProperties"].as_bool()) {
+            (Value::Object(map), Some(false)) => schema["required"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .all(|key| map.contains_key(key)),
+            _ => true,
+        }
+    }
+
+    fn names(&self, schema: &'a Value) -> Vec<&'a str> {
+        let schema = self.resolve(schema);
+        match schema["enum"].as_array() {
+            Some(variants) => variants.iter().filter_map(Value::as_str).collect(),
+            None if schema["additionalProperties"] == false => schema["required"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn passes(&self, schema: &'a Value, value: &Value) -> bool {
+        let mut scratch = Validator {
+            defs: self.defs,
+            strict: false,
+            partial: self.partial,
+            pointer: Vec::new(),
+            diagnostics: Vec::new(),
+            patterns: HashMap::new(),
+        };
+        scratch.check(schema, value);
+        scratch.diagnostics.is_empty()
+    }
+}
+
+#[cfg(feature = "schema")]
+fn alternatives(schema: &Value) -> Option<&[Value]> {
+    schema["anyOf"]
+        .as_array()
+        .or_else(|| schema["oneOf"].as_array())
+        .map(Vec::as_slice)
+}
+
+#[cfg(feature = "schema")]
+type Parser = fn(&Value) -> Result<(), serde_json::Error>;
+
+// Types with rules the schema can only approximate, checked with their own parsers.
+#[cfg(feature = "schema")]
+fn parser(name: &str) -> Option<Parser> {
+    match name {
+        "PartialDate" => Some(|x| PartialDate::deserialize(x).map(drop)),
+        "EndDate" => Some(|x| EndDate::deserialize(x).map(drop)),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "schema")]
+fn has_type(ty: &str, value: &Value) -> bool {
+    match (ty, value) {
+        ("null", Value::Null)
+        | ("boolean", Value::Bool(_))
+        | ("number", Value::Number(_))
+        | ("string", Value::String(_))
+        | ("array", Value::Array(_))
+        | ("object", Value::Object(_)) => true,
+        ("integer", Value::Number(n)) => n.is_i64() || n.is_u64(),
+        _ => false,
+    }
+}
+
+// Describes what a schema accepts, for messages such as "expected a string, found a number".
+#[cfg(feature = "schema")]
+fn expected(schema: &Value) -> String {
+    let types: Vec<&str> = match &schema["type"] {
+        Value::String(ty) => vec![ty],
+        Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+        _ => return "another kind of value".to_string(),
+    };
+    let described: Vec<&str> = types
+        .into_iter()
+        .map(|ty| match ty {
+            "boolean" => "a boolean",
+            "integer" => "an integer",
+            "number" => "a number",
+            "string" => "a string",
+            "array" => "an array",
+            "object" => "an object",
+            _ => "null",
+        })
+        .collect();
+    described.join(" or ")
+}
+
+#[cfg(feature = "schema")]
+fn unknown_variant(value: &Value, names: &[&str]) -> String {
+    let found = match value {
+        Value::String(name) => name.clone(),
+        Value::Object(map) => map.keys().next().cloned().unwrap_or_default(),
+        other => other.to_string(),
+    };
+    let names: Vec<String> = names.iter().map(|name| format!("`{}`", name)).collect();
+    format!(
+        "unknown variant `{}`, expected one of {}",
+        found,
+        names.join(", ")
+    )
+}
+
+#[cfg(feature = "schema")]
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// Picks the known field closest to a misspelled key, ignoring case.
+#[cfg(feature = "schema")]
+fn suggest<'a>(key: &str, known: &'a [String]) -> Option<&'a str> {
+    let key = key.to_lowercase();
+    known
+        .iter()
+        .map(|candidate| (edit_distance(&key, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= (key.chars().count() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+// Levenshtein distance over chars.
+#[cfg(feature = "schema")]
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::InputFormat;
+
+    fn walk(json: &str, strict: bool) -> Vec<Diagnostic> {
+        check(&InputFormat::Json.parse(json).unwrap(), strict, false)
+    }
+
+    #[test]
+    #[cfg(feature = "schema")]
+    fn test_collects_every_error() {
+        let diagnostics = walk(
+            r#"{
+                "basics": { "email": "nope", "location": { "countryCode": "usa" } },
+                "work": [
+                    { "startDate": "2020-01" },
+                    { "startDate": "someday", "employmentType": "Gig" }
+                ],
+                "languages": [{ "language": "Klingon" }],
+                "education": [{ "score": { "Grade": "A" }, "endDate": "2023-02-30" }]
+            }"#,
+            false,
+        );
+        let found: Vec<(&str, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.pointer.as_str(), d.rule))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("/basics/email", "invalid-email"),
+                ("/basics/location/countryCode", "invalid-value"),
+                ("/work/1/startDate", "invalid-date"),
+                ("/work/1/employmentType", "unknown-variant"),
+                ("/languages/0/language", "unknown-variant"),
+                ("/education/0/score", "unknown-variant"),
+                ("/education/0/endDate", "invalid-date"),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "schema")]
+    fn test_reports_shape_and_missing_fields() {
+        let diagnostics = walk(
+            r#"{ "work": {}, "meta": { "version": { "major": 1, "minor": 0 } } }"#,
+            false,
+        );
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].pointer, "/work");
+        assert_eq!(diagnostics[0].rule, "invalid-type");
+        assert_eq!(diagnostics[1].pointer, "/meta/version");
+        assert_eq!(diagnostics[1].rule, "missing-field");
+        assert!(diagnostics[1].message.contains("`patch`"));
+    }
+
+    #[test]
+    #[cfg(feature = "schema")]
+    fn test_descends_into_the_matching_variant() {
+        let diagnostics = walk(
+            r#"{ "work": [{ "location": { "Hybrid": { "onSite": 3 } },
+                           "highlights": ["Plain", { "tags": ["rust"] }] }] }"#,
+            false,
+        );
+        let found: Vec<(&str, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.pointer.as_str(), d.rule))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("/work/0/location/Hybrid/onSite", "invalid-type"),
+                ("/work/0/highlights/1", "missing-field"),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "schema")]
+    fn test_strict_reports_unknown_fields() {
+        let json = r#"{
+            "work": [{ "name": "Acme", "highlight": ["Shipped"], "startdate": "2020-01" }],
+            "basics": { "x-clearance": "secret", "nickname": "Ada" }
+        }"#;
+        assert!(walk(json, false).is_empty());
+
+        let diagnostics = walk(json, true);
+        let found: Vec<(&str, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.pointer.as_str(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "/work/0/highlight",
+                    "unknown field `highlight`, did you mean `highlights`?"
+                ),
+                (
+                    "/work/0/startdate",
+                    "unknown field `startdate`, did you mean `startDate`?"
+                ),
+                ("/basics/nickname", "unknown field `nickname`"),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "schema")]
+    fn test_findings_follow_the_file() {
+        let source = "{\n  \"work\": [{ \"endDate\": \"later\", \"startDate\": \"soon\" }],\n  \"basics\": { \"email\": \"nope\" }\n}";
+        let document = Document::parse(Path::new("resume.json"), source, None).unwrap();
+        let diagnostics = validate(&document, false);
+        let found: Vec<(&str, String)> = diagnostics
+            .iter()
+            .map(|d| (d.pointer.as_str(), d.location.unwrap().to_string()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("/work/0/endDate", "2:25".to_string()),
+                ("/work/0/startDate", "2:47".to_string()),
+                ("/basics/email", "3:24".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_valid_resume_has_no_diagnostics() {
+        let diagnostics = walk(
+            r#"{
+                "basics": { "name": "Ada", "email": "ada@example.com" },
+                "work": [{ "startDate": "2020-01", "endDate": "present",
+                           "location": { "OnSite": "London" }, "highlights": ["a", { "text": "b" }] }],
+                "education": [{ "score": { "Percentage": 91 } }],
+                "languages": [{ "language": { "Other": "Toki Pona" } }]
+            }"#,
+            false,
+        );
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+}
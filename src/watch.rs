@@ -0,0 +1,101 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Every file below `path`, or `path` itself when it is not a directory.
+pub fn files(path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return vec![path.to_path_buf()];
+    };
+    let mut found: Vec<PathBuf> = entries
+        .flatten()
+        .flat_map(|entry| files(&entry.path()))
+        .collect();
+    found.sort();
+    found
+}
+
+/// Notices changes to a set of files and directories by polling their modification times,
+/// which needs no platform support and copes with editors that replace files on save.
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    stamps: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl Watcher {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let mut watcher = Self {
+            paths,
+            stamps: Vec::new(),
+        };
+        watcher.stamps = watcher.snapshot();
+        watcher
+    }
+
+    fn snapshot(&self) -> Vec<(PathBuf, Option<SystemTime>)> {
+        self.paths
+            .iter()
+            .flat_map(|path| files(path))
+            .map(|file| {
+                let modified = fs::metadata(&file).and_then(|m| m.modified()).ok();
+                (file, modified)
+            })
+            .collect()
+    }
+
+    /// Whether anything was added, removed or modified since the last call.
+    fn changed(&mut self) -> bool {
+        let snapshot = self.snapshot();
+        if snapshot == self.stamps {
+            return false;
+        }
+        self.stamps = snapshot;
+        true
+    }
+
+    /// Blocks until something changes, then waits for writes to settle.
+    pub fn wait(&mut self) {
+        while !self.changed() {
+            thread::sleep(POLL_INTERVAL);
+        }
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            if !self.changed() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watcher_sees_new_and_modified_files() {
+        let dir = std::env::temp_dir().join(format!("resume-watch-{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        let file = dir.join("nested/template.typ");
+        fs::write(&file, "= Old").unwrap();
+
+        let mut watcher = Watcher::new(vec![dir.clone()]);
+        assert!(!watcher.changed());
+
+        fs::write(dir.join("extra.typ"), "").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        let later = SystemTime::now() + Duration::from_secs(5);
+        fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert!(watcher.changed());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -4,6 +4,8 @@
 
 // Get data from sys.inputs (native Dict from Rust)
 #let data = sys.inputs
+// Template parameters from `--param` or a build profile
+#let params = data.at("params", default: (:))
 
 // Configure text and headings
 #set text(font: "New Computer Modern", 10pt)
@@ -14,16 +16,17 @@
 
 
 // --- Helper: Format Date ---
-#let format-date(date-str) = {
-  if type(date-str) != str or date-str == "" { return none }
-  let parts = date-str.split("-")
-  if parts.len() < 2 { return date-str }
-  
+// Dates arrive as dicts: (year, month, day, precision), or (precision: "present").
+#let format-date(date) = {
+  if type(date) != dictionary { return none }
+  if date.precision == "present" { return "Present" }
+
   let months = (
-    "01": "Jan", "02": "Feb", "03": "Mar", "04": "Apr", "05": "May", "06": "Jun",
-    "07": "Jul", "08": "Aug", "09": "Sep", "10": "Oct", "11": "Nov", "12": "Dec"
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun",
+    "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"
   )
-  months.at(parts.at(1), default: parts.at(1)) + " " + parts.at(0)
+  if date.precision == "year" { str(date.year) }
+  else { months.at(date.month - 1) + " " + str(date.year) }
 }
 
 // --- Convert sys.inputs data to typed Resume object ---
@@ -63,8 +66,8 @@
       name: w.at("name", default: none),
       position: w.at("position", default: none),
       url: w.at("url", default: none),
-      startDate: format-date(w.at("startDate", default: "")),
-      endDate: format-date(w.at("endDate", default: "")),
+      startDate: format-date(w.at("startDate", default: none)),
+      endDate: format-date(w.at("endDate", default: none)),
       summary: w.at("summary", default: none),
       highlights: w.at("highlights", default: none),
     ))
@@ -75,8 +78,8 @@
       url: e.at("url", default: none),
       area: e.at("area", default: none),
       studyType: e.at("studyType", default: none),
-      startDate: format-date(e.at("startDate", default: "")),
-      endDate: format-date(e.at("endDate", default: "")),
+      startDate: format-date(e.at("startDate", default: none)),
+      endDate: format-date(e.at("endDate", default: none)),
       score: e.at("score", default: none),
       courses: e.at("courses", default: none),
     ))
@@ -87,8 +90,8 @@
       description: p.at("description", default: none),
       highlights: p.at("highlights", default: none),
       keywords: p.at("keywords", default: none),
-      startDate: format-date(p.at("startDate", default: "")),
-      endDate: format-date(p.at("endDate", default: "")),
+      startDate: format-date(p.at("startDate", default: none)),
+      endDate: format-date(p.at("endDate", default: none)),
       url: p.at("url", default: none),
       roles: p.at("roles", default: none),
       entity: p.at("entity", default: none),
@@ -102,10 +105,43 @@
       keywords: s.at("keywords", default: none),
     ))
   } else { none },
-  certificates: data.at("certificates", default: none),
-  awards: data.at("awards", default: none),
-  publications: data.at("publications", default: none),
-  volunteer: data.at("volunteer", default: none),
+  certificates: if data.at("certificates", default: none) != none {
+    data.certificates.map(c => (
+      name: c.at("name", default: none),
+      date: format-date(c.at("date", default: none)),
+      issuer: c.at("issuer", default: none),
+      url: c.at("url", default: none),
+      expirationDate: format-date(c.at("expirationDate", default: none)),
+    ))
+  } else { none },
+  awards: if data.at("awards", default: none) != none {
+    data.awards.map(a => (
+      title: a.at("title", default: none),
+      date: format-date(a.at("date", default: none)),
+      awarder: a.at("awarder", default: none),
+      summary: a.at("summary", default: none),
+    ))
+  } else { none },
+  publications: if data.at("publications", default: none) != none {
+    data.publications.map(p => (
+      name: p.at("name", default: none),
+      publisher: p.at("publisher", default: none),
+      releaseDate: format-date(p.at("releaseDate", default: none)),
+      url: p.at("url", default: none),
+      summary: p.at("summary", default: none),
+    ))
+  } else { none },
+  volunteer: if data.at("volunteer", default: none) != none {
+    data.volunteer.map(v => (
+      organization: v.at("organization", default: none),
+      position: v.at("position", default: none),
+      url: v.at("url", default: none),
+      startDate: format-date(v.at("startDate", default: none)),
+      endDate: format-date(v.at("endDate", default: none)),
+      summary: v.at("summary", default: none),
+      highlights: v.at("highlights", default: none),
+    ))
+  } else { none },
   languages: data.at("languages", default: none),
   interests: data.at("interests", default: none),
   references: data.at("references", default: none),
@@ -114,12 +150,12 @@
 // --- Render the resume using the custom template ---
 #show: resume.with(
   json-resume,
-  accent-color: rgb("#5E81AC"),
-  font: "New Computer Modern",
-  paper: "us-letter",
+  accent-color: rgb(params.at("accent-color", default: "#5E81AC")),
+  font: params.at("font", default: "New Computer Modern"),
+  paper: params.at("paper", default: "us-letter"),
   author-font-size: 20pt,
   font-size: 10pt,
-  lang: "en",
+  lang: params.at("lang", default: "en"),
 )
 
 // Render all sections